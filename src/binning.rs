@@ -0,0 +1,292 @@
+// src/binning.rs
+
+use serde::Serialize;
+
+/// Obergrenze der Klassenanzahl, damit eine kleine Breite keine Millionen Klassen erzeugt
+pub const MAX_BINS: usize = 10_000;
+
+/// Verfahren zur Bestimmung der Klassengrenzen
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum BinStrategy {
+    /// Feste Breite; die Grenzen liegen auf Vielfachen der Breite
+    FixedWidth(f64),
+    /// Feste Anzahl gleich breiter Klassen zwischen Minimum und Maximum
+    FixedCount(usize),
+    /// ⌈log₂ n⌉ + 1 Klassen
+    Sturges,
+    /// Breite 3.49 · σ · n^(-1/3)
+    Scott,
+    /// Breite 2 · IQR · n^(-1/3) (Freedman–Diaconis)
+    #[default]
+    FreedmanDiaconis,
+    /// Klassen mit (etwa) gleich vielen Werten
+    Quantile(usize),
+    /// Vorgegebene, streng steigende Grenzen
+    Edges(Vec<f64>),
+}
+
+impl BinStrategy {
+    /// Verfahren aus den Query-Parametern `strategy` und `bins`. `bins` ist je nach Verfahren
+    /// die Anzahl (`count`, `quantile`), die Breite (`width`) oder eine Liste von Grenzen (`edges`).
+    /// Ohne `strategy` gilt eine Liste als Grenzen und eine Zahl als Anzahl; ohne beides `None`.
+    pub fn parse(strategy: Option<&str>, bins: Option<&str>) -> Result<Option<BinStrategy>, String> {
+        let bins = bins.map(str::trim).filter(|b| !b.is_empty());
+        let strategy = match strategy.map(|s| s.trim().to_lowercase()) {
+            Some(strategy) => strategy,
+            None => match bins {
+                None => return Ok(None),
+                Some(bins) if bins.contains(',') => "edges".to_string(),
+                Some(_) => "count".to_string(),
+            },
+        };
+        let count = |name: &str| -> Result<usize, String> {
+            let bins = bins.ok_or_else(|| format!("strategy '{name}' braucht die Anzahl in 'bins'"))?;
+            match bins.parse::<usize>() {
+                Ok(n) if (1..=MAX_BINS).contains(&n) => Ok(n),
+                _ => Err(format!("bins muss eine Anzahl zwischen 1 und {MAX_BINS} sein, nicht '{bins}'")),
+            }
+        };
+        let strategy = match strategy.as_str() {
+            "width" | "fixed-width" => {
+                let bins = bins.ok_or("strategy 'width' braucht die Klassenbreite in 'bins'")?;
+                match bins.parse::<f64>() {
+                    Ok(width) if width.is_finite() && width > 0.0 => BinStrategy::FixedWidth(width),
+                    _ => return Err(format!("Klassenbreite '{bins}' muss eine Zahl größer als 0 sein")),
+                }
+            }
+            "count" | "fixed-count" => BinStrategy::FixedCount(count("count")?),
+            "quantile" => BinStrategy::Quantile(count("quantile")?),
+            "sturges" => BinStrategy::Sturges,
+            "scott" => BinStrategy::Scott,
+            "fd" | "freedman-diaconis" => BinStrategy::FreedmanDiaconis,
+            "edges" => {
+                let bins = bins.ok_or("strategy 'edges' braucht die Grenzen in 'bins', z. B. 0,40000,60000")?;
+                let edges = bins
+                    .split(',')
+                    .map(|edge| edge.trim().parse::<f64>().map_err(|_| format!("Grenze '{edge}' ist keine Zahl")))
+                    .collect::<Result<Vec<f64>, String>>()?;
+                BinStrategy::Edges(validate_edges(edges)?)
+            }
+            other => {
+                return Err(format!(
+                    "strategy '{other}' ist unbekannt (width, count, sturges, scott, fd, quantile, edges)"
+                ))
+            }
+        };
+        Ok(Some(strategy))
+    }
+
+    /// Kurzname wie in `strategy`
+    pub fn label(&self) -> &'static str {
+        match self {
+            BinStrategy::FixedWidth(_) => "width",
+            BinStrategy::FixedCount(_) => "count",
+            BinStrategy::Sturges => "sturges",
+            BinStrategy::Scott => "scott",
+            BinStrategy::FreedmanDiaconis => "fd",
+            BinStrategy::Quantile(_) => "quantile",
+            BinStrategy::Edges(_) => "edges",
+        }
+    }
+
+    /// Klassengrenzen für die Werte. Ohne Werte gibt es keine Klassen (außer bei vorgegebenen
+    /// Grenzen); sind alle Werte gleich, liegt eine Klasse der Breite 1 um den Wert.
+    pub fn edges(&self, values: &[f64]) -> Result<Vec<f64>, String> {
+        if let BinStrategy::Edges(edges) = self {
+            return Ok(edges.clone());
+        }
+        let mut sorted = values.iter().copied().filter(|v| v.is_finite()).collect::<Vec<f64>>();
+        if sorted.is_empty() {
+            return Ok(Vec::new());
+        }
+        sorted.sort_by(f64::total_cmp);
+        let (min, max) = (sorted[0], sorted[sorted.len() - 1]);
+        let n = sorted.len() as f64;
+
+        if let BinStrategy::FixedWidth(width) = self {
+            let start = (min / width).floor() * width;
+            let count = (((max - start) / width).ceil() as usize).max(1);
+            check_count(count)?;
+            // Liegt das Maximum genau auf der letzten Grenze, gehört es noch in die letzte Klasse
+            let mut edges = (0..=count).map(|i| start + i as f64 * width).collect::<Vec<f64>>();
+            edges[count] = edges[count].max(max);
+            return Ok(edges);
+        }
+        if min == max {
+            let count = match self {
+                BinStrategy::FixedCount(count) => *count,
+                _ => 1,
+            };
+            return Ok(equal_width(min - 0.5, max + 0.5, count));
+        }
+        let count = match self {
+            BinStrategy::FixedCount(count) => *count,
+            BinStrategy::Sturges => sturges(n),
+            BinStrategy::Scott => {
+                let mean = sorted.iter().sum::<f64>() / n;
+                let std = (sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0)).sqrt();
+                count_for_width(max - min, 3.49 * std * n.powf(-1.0 / 3.0), n)?
+            }
+            BinStrategy::FreedmanDiaconis => {
+                let iqr = sorted_quantile(&sorted, 0.75) - sorted_quantile(&sorted, 0.25);
+                count_for_width(max - min, 2.0 * iqr * n.powf(-1.0 / 3.0), n)?
+            }
+            BinStrategy::Quantile(count) => {
+                let mut edges = (0..=*count)
+                    .map(|i| sorted_quantile(&sorted, i as f64 / *count as f64))
+                    .collect::<Vec<f64>>();
+                // Bindungen ergeben gleiche Grenzen, leere Klassen fallen weg
+                edges.dedup();
+                return Ok(edges);
+            }
+            BinStrategy::FixedWidth(_) | BinStrategy::Edges(_) => unreachable!(),
+        };
+        Ok(equal_width(min, max, count))
+    }
+
+    /// Klassengrenzen bestimmen und die Werte einordnen
+    pub fn histogram(&self, values: &[f64]) -> Result<Histogram, String> {
+        let edges = self.edges(values)?;
+        Ok(Histogram::count(self.label(), edges, values))
+    }
+}
+
+/// Vorgegebene Grenzen prüfen: mindestens zwei, endlich und streng steigend
+fn validate_edges(edges: Vec<f64>) -> Result<Vec<f64>, String> {
+    if edges.len() < 2 {
+        return Err("mindestens zwei Grenzen sind nötig".to_string());
+    }
+    if edges.iter().any(|e| !e.is_finite()) || edges.windows(2).any(|w| w[0] >= w[1]) {
+        return Err("Grenzen müssen endlich und streng steigend sein".to_string());
+    }
+    check_count(edges.len() - 1)?;
+    Ok(edges)
+}
+
+fn check_count(count: usize) -> Result<(), String> {
+    if count > MAX_BINS {
+        return Err(format!("{count} Klassen sind zu viele (höchstens {MAX_BINS})"));
+    }
+    Ok(())
+}
+
+fn sturges(n: f64) -> usize {
+    n.log2().ceil() as usize + 1
+}
+
+/// Anzahl Klassen für eine Breitenregel; ohne Streuung (Breite 0) wie bei Sturges
+fn count_for_width(range: f64, width: f64, n: f64) -> Result<usize, String> {
+    if !(width.is_finite() && width > 0.0) {
+        return Ok(sturges(n));
+    }
+    let count = ((range / width).ceil() as usize).max(1);
+    check_count(count)?;
+    Ok(count)
+}
+
+fn equal_width(min: f64, max: f64, count: usize) -> Vec<f64> {
+    let width = (max - min) / count as f64;
+    // Letzte Grenze exakt auf das Maximum setzen, damit Rundungsfehler es nicht ausschließen
+    (0..=count).map(|i| if i == count { max } else { min + i as f64 * width }).collect()
+}
+
+/// Quantil einer sortierten Liste mit linearer Interpolation
+fn sorted_quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (pos.floor() as usize, pos.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (pos - lower as f64)
+}
+
+/// Eingeordnete Werte. Jede Klasse umfasst ihre linke Grenze, die letzte auch die rechte.
+#[derive(Debug, Clone, Serialize)]
+pub struct Histogram {
+    pub strategy: &'static str,
+    pub edges: Vec<f64>,
+    pub centers: Vec<f64>,
+    pub counts: Vec<usize>,
+    /// Anzahl je Klasse geteilt durch (eingeordnete Werte · Klassenbreite), Fläche = 1
+    pub density: Vec<f64>,
+    /// Werte außerhalb vorgegebener Grenzen
+    pub outside: usize,
+}
+
+impl Histogram {
+    /// Werte auf gegebene Grenzen verteilen (z. B. gemeinsame Grenzen mehrerer Gruppen)
+    pub fn count(strategy: &'static str, edges: Vec<f64>, values: &[f64]) -> Histogram {
+        let bins = edges.len().saturating_sub(1);
+        let mut counts = vec![0usize; bins];
+        let mut outside = 0;
+        for &value in values {
+            match bin_index(&edges, value) {
+                Some(idx) => counts[idx] += 1,
+                None => outside += 1,
+            }
+        }
+        let inside = counts.iter().sum::<usize>() as f64;
+        let density = edges
+            .windows(2)
+            .zip(&counts)
+            .map(|(w, &c)| if inside > 0.0 { c as f64 / (inside * (w[1] - w[0])) } else { 0.0 })
+            .collect();
+        Histogram {
+            strategy,
+            centers: edges.windows(2).map(|w| (w[0] + w[1]) / 2.0).collect(),
+            edges,
+            counts,
+            density,
+            outside,
+        }
+    }
+}
+
+/// Klasse eines Werts; die letzte Grenze gehört noch zur letzten Klasse
+pub fn bin_index(edges: &[f64], value: f64) -> Option<usize> {
+    let (first, last) = (*edges.first()?, *edges.last()?);
+    if edges.len() < 2 || !(value >= first && value <= last) {
+        return None;
+    }
+    let idx = edges.partition_point(|&e| e <= value) - 1;
+    Some(idx.min(edges.len() - 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_edge_is_inclusive() {
+        let histogram = BinStrategy::FixedWidth(10_000.0).histogram(&[10_000.0, 25_000.0, 40_000.0]).unwrap();
+        assert_eq!(histogram.edges, vec![10_000.0, 20_000.0, 30_000.0, 40_000.0]);
+        assert_eq!(histogram.counts, vec![1, 1, 1]);
+        let histogram = BinStrategy::FixedCount(4).histogram(&[0.0, 1.0, 2.0, 3.0, 4.0]).unwrap();
+        assert_eq!(histogram.counts, vec![1, 1, 1, 2]);
+        assert_eq!(histogram.outside, 0);
+    }
+
+    #[test]
+    fn empty_and_constant_input() {
+        let histogram = BinStrategy::Sturges.histogram(&[]).unwrap();
+        assert!(histogram.edges.is_empty() && histogram.counts.is_empty());
+        let histogram = BinStrategy::FreedmanDiaconis.histogram(&[5.0, 5.0, 5.0]).unwrap();
+        assert_eq!(histogram.edges, vec![4.5, 5.5]);
+        assert_eq!(histogram.counts, vec![3]);
+        let histogram = BinStrategy::Quantile(4).histogram(&[5.0; 4]).unwrap();
+        assert_eq!(histogram.counts, vec![4]);
+    }
+
+    #[test]
+    fn rules_and_explicit_edges() {
+        let values = (1..=100).map(f64::from).collect::<Vec<f64>>();
+        assert_eq!(BinStrategy::Sturges.edges(&values).unwrap().len(), 9);
+        let histogram = BinStrategy::Quantile(4).histogram(&values).unwrap();
+        assert_eq!(histogram.counts, vec![25, 25, 25, 25]);
+        let strategy = BinStrategy::parse(None, Some("0, 50, 80")).unwrap().unwrap();
+        let histogram = strategy.histogram(&values).unwrap();
+        assert_eq!(histogram.counts, vec![49, 31]);
+        assert_eq!(histogram.outside, 20);
+        let area = histogram.density.iter().zip(histogram.edges.windows(2)).map(|(d, w)| d * (w[1] - w[0]));
+        assert!((area.sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(BinStrategy::parse(Some("edges"), Some("3,1")).is_err());
+        assert!(BinStrategy::parse(Some("width"), Some("0")).is_err());
+    }
+}
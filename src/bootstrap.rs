@@ -0,0 +1,406 @@
+// src/bootstrap.rs
+
+use crate::data_analysis::{median, quantile};
+use crate::inference::{normal_cdf, normal_quantile};
+use serde_json::json;
+
+/// Standardanzahl der Stichprobenwiederholungen
+pub const DEFAULT_RESAMPLES: usize = 1000;
+
+/// Obergrenze, damit eine Anfrage den Server nicht blockiert
+pub const MAX_RESAMPLES: usize = 100_000;
+
+/// Startwert des Zufallsgenerators, wenn die Anfrage keinen angibt
+pub const DEFAULT_SEED: u64 = 42;
+
+/// Standard-Konfidenzniveau
+pub const DEFAULT_CONFIDENCE: f64 = 0.95;
+
+/// Art des Konfidenzintervalls
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IntervalMethod {
+    /// Quantile der Bootstrap-Verteilung
+    Percentile,
+    /// Bias-korrigiert und beschleunigt (Efron)
+    Bca,
+}
+
+impl IntervalMethod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            IntervalMethod::Percentile => "percentile",
+            IntervalMethod::Bca => "bca",
+        }
+    }
+}
+
+/// Einstellungen des Bootstraps
+#[derive(Debug, Clone, Copy)]
+pub struct BootstrapConfig {
+    pub method: IntervalMethod,
+    pub resamples: usize,
+    pub seed: u64,
+    pub confidence: f64,
+}
+
+impl Default for BootstrapConfig {
+    fn default() -> Self {
+        BootstrapConfig {
+            method: IntervalMethod::Percentile,
+            resamples: DEFAULT_RESAMPLES,
+            seed: DEFAULT_SEED,
+            confidence: DEFAULT_CONFIDENCE,
+        }
+    }
+}
+
+impl BootstrapConfig {
+    /// Einstellungen aus Query-Parametern. Intervalle gibt es nur mit `ci=percentile` oder
+    /// `ci=bca`; ohne `ci` oder mit `ci=none` bleibt es bei den Punktschätzern.
+    pub fn from_params(
+        ci: Option<&str>,
+        resamples: Option<usize>,
+        seed: Option<u64>,
+        confidence: Option<f64>,
+    ) -> Result<Option<BootstrapConfig>, String> {
+        let method = match ci.map(|c| c.trim().to_lowercase()).as_deref() {
+            None | Some("none") => return Ok(None),
+            Some("percentile") => IntervalMethod::Percentile,
+            Some("bca") => IntervalMethod::Bca,
+            Some(other) => return Err(format!("ci '{other}' ist weder 'percentile', 'bca' noch 'none'")),
+        };
+        let resamples = resamples.unwrap_or(DEFAULT_RESAMPLES);
+        if !(1..=MAX_RESAMPLES).contains(&resamples) {
+            return Err(format!("resamples muss zwischen 1 und {MAX_RESAMPLES} liegen"));
+        }
+        let confidence = confidence.unwrap_or(DEFAULT_CONFIDENCE);
+        if !(confidence > 0.0 && confidence < 1.0) {
+            return Err("confidence muss zwischen 0 und 1 liegen".to_string());
+        }
+        Ok(Some(BootstrapConfig {
+            method,
+            resamples,
+            seed: seed.unwrap_or(DEFAULT_SEED),
+            confidence,
+        }))
+    }
+
+    /// Einstellungen für die Antwort
+    pub fn describe(&self) -> serde_json::Value {
+        json!({
+            "method": self.method.label(),
+            "resamples": self.resamples,
+            "seed": self.seed,
+            "confidence": self.confidence,
+        })
+    }
+}
+
+/// Kennzahl, für die ein Intervall geschätzt wird
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Statistic {
+    Mean,
+    Median,
+    Quantile(f64),
+}
+
+impl Statistic {
+    pub fn compute(&self, values: &[f64]) -> f64 {
+        match self {
+            Statistic::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            Statistic::Median => median(values),
+            Statistic::Quantile(q) => quantile(values, *q),
+        }
+    }
+
+    /// Wie `compute`, ordnet aber die (ohnehin neu gezogene) Stichprobe um, statt sie zu
+    /// kopieren und zu sortieren. Quantile per Auswahl in linearer Zeit.
+    fn compute_in_place(&self, values: &mut [f64]) -> f64 {
+        let q = match self {
+            Statistic::Mean => return self.compute(values),
+            Statistic::Median => 0.5,
+            Statistic::Quantile(q) => q.clamp(0.0, 1.0),
+        };
+        let pos = q * (values.len() - 1) as f64;
+        let lower = pos.floor() as usize;
+        let (_, low, above) = values.select_nth_unstable_by(lower, f64::total_cmp);
+        let low = *low;
+        if pos > lower as f64 {
+            let high = above.iter().copied().fold(f64::INFINITY, f64::min);
+            low + (high - low) * (pos - lower as f64)
+        } else {
+            low
+        }
+    }
+
+    /// Jackknife-Werte (Kennzahl ohne je eine Beobachtung). Für Median und Quantile
+    /// genügt einmal Sortieren, da das Weglassen nur die Indizes verschiebt.
+    fn jackknife(&self, values: &[f64]) -> Vec<f64> {
+        let n = values.len();
+        let q = match self {
+            Statistic::Mean => {
+                let sum = values.iter().sum::<f64>();
+                return values.iter().map(|x| (sum - x) / (n - 1) as f64).collect();
+            }
+            Statistic::Median => 0.5,
+            Statistic::Quantile(q) => q.clamp(0.0, 1.0),
+        };
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let pos = q * (n - 2) as f64;
+        let (lower, upper) = (pos.floor() as usize, pos.ceil() as usize);
+        let weight = pos - lower as f64;
+        // Fehlt der Wert mit Rang `skip`, rücken alle höheren Ränge um eins nach.
+        // Die Zuordnung zu den Beobachtungen spielt für die Beschleunigung keine Rolle.
+        let at = |skip: usize, k: usize| if k < skip { sorted[k] } else { sorted[k + 1] };
+        (0..n)
+            .map(|skip| at(skip, lower) + (at(skip, upper) - at(skip, lower)) * weight)
+            .collect()
+    }
+}
+
+/// Punktschätzung mit Konfidenzintervall und Bootstrap-Standardfehler
+#[derive(Debug, Clone, Copy)]
+pub struct Interval {
+    pub estimate: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub std_error: f64,
+}
+
+impl Interval {
+    pub fn describe(&self) -> serde_json::Value {
+        json!({
+            "estimate": self.estimate,
+            "lower": self.lower,
+            "upper": self.upper,
+            "std_error": self.std_error,
+        })
+    }
+}
+
+/// SplitMix64: klein, schnell und über Versionen stabil, damit ein Seed immer dieselben Intervalle liefert
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Eigener Generator je Wiederholung, damit das Ergebnis nicht von der Anzahl Threads abhängt
+    fn for_replicate(seed: u64, replicate: usize) -> Self {
+        let mut base = SplitMix64 { state: seed };
+        let mixed = base.next_u64() ^ (replicate as u64).wrapping_mul(0xd1b5_4a32_d192_ed03);
+        SplitMix64 { state: SplitMix64 { state: mixed }.next_u64() }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Gleichverteilter Index in `0..n`
+    fn below(&mut self, n: usize) -> usize {
+        ((u128::from(self.next_u64()) * n as u128) >> 64) as usize
+    }
+}
+
+/// Ziehen mit Zurücklegen
+fn resample(values: &[f64], rng: &mut SplitMix64, out: &mut Vec<f64>) {
+    out.clear();
+    out.extend((0..values.len()).map(|_| values[rng.below(values.len())]));
+}
+
+/// Bootstrap-Replikate parallel auf alle verfügbaren Kerne verteilt berechnen
+fn replicates<F>(config: &BootstrapConfig, replicate: F) -> Vec<f64>
+where
+    F: Fn(&mut SplitMix64) -> f64 + Sync,
+{
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    replicates_on(threads, config, replicate)
+}
+
+/// Wie `replicates`, mit fester Anzahl Threads
+fn replicates_on<F>(threads: usize, config: &BootstrapConfig, replicate: F) -> Vec<f64>
+where
+    F: Fn(&mut SplitMix64) -> f64 + Sync,
+{
+    let threads = threads.clamp(1, config.resamples);
+    let chunk = config.resamples.div_ceil(threads);
+    let mut results = vec![0.0; config.resamples];
+    std::thread::scope(|scope| {
+        for (part, slots) in results.chunks_mut(chunk).enumerate() {
+            let replicate = &replicate;
+            scope.spawn(move || {
+                for (offset, slot) in slots.iter_mut().enumerate() {
+                    let mut rng = SplitMix64::for_replicate(config.seed, part * chunk + offset);
+                    *slot = replicate(&mut rng);
+                }
+            });
+        }
+    });
+    results
+}
+
+/// Intervall aus den Replikaten; für BCa werden zusätzlich die Jackknife-Werte gebraucht
+fn interval(config: &BootstrapConfig, estimate: f64, mut boots: Vec<f64>, jackknife: impl FnOnce() -> Vec<f64>) -> Interval {
+    boots.retain(|b| b.is_finite());
+    let n = boots.len() as f64;
+    let mean = boots.iter().sum::<f64>() / n;
+    let std_error = (boots.iter().map(|b| (b - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+    let alpha = (1.0 - config.confidence) / 2.0;
+    let (low, high) = match config.method {
+        IntervalMethod::Percentile => (alpha, 1.0 - alpha),
+        IntervalMethod::Bca => bca_levels(estimate, &boots, &jackknife(), alpha),
+    };
+    Interval {
+        estimate,
+        lower: quantile(&boots, low),
+        upper: quantile(&boots, high),
+        std_error,
+    }
+}
+
+/// Korrigierte Quantil-Niveaus des BCa-Intervalls. Ohne Streuung der Replikate
+/// oder der Jackknife-Werte bleibt es beim Perzentil-Intervall.
+fn bca_levels(estimate: f64, boots: &[f64], jackknife: &[f64], alpha: f64) -> (f64, f64) {
+    let below = boots.iter().filter(|&&b| b < estimate).count() as f64;
+    let equal = boots.iter().filter(|&&b| b == estimate).count() as f64;
+    let share = (below + equal / 2.0) / boots.len() as f64;
+    if share <= 0.0 || share >= 1.0 {
+        return (alpha, 1.0 - alpha);
+    }
+    let z0 = normal_quantile(share);
+
+    let jack_mean = jackknife.iter().sum::<f64>() / jackknife.len() as f64;
+    let (mut num, mut den) = (0.0, 0.0);
+    for value in jackknife {
+        let d = jack_mean - value;
+        num += d * d * d;
+        den += d * d;
+    }
+    let acceleration = if den > 0.0 { num / (6.0 * den.powf(1.5)) } else { 0.0 };
+
+    let adjust = |level: f64| {
+        let z = z0 + normal_quantile(level);
+        normal_cdf(z0 + z / (1.0 - acceleration * z))
+    };
+    (adjust(alpha), adjust(1.0 - alpha))
+}
+
+/// Konfidenzintervall einer Kennzahl; `None` bei weniger als zwei Werten
+pub fn bootstrap_statistic(values: &[f64], statistic: Statistic, config: &BootstrapConfig) -> Option<Interval> {
+    if values.len() < 2 {
+        return None;
+    }
+    let estimate = statistic.compute(values);
+    let boots = replicates(config, |rng| {
+        let mut sample = Vec::with_capacity(values.len());
+        resample(values, rng, &mut sample);
+        statistic.compute_in_place(&mut sample)
+    });
+    let jackknife = || statistic.jackknife(values);
+    Some(interval(config, estimate, boots, jackknife))
+}
+
+/// Konfidenzintervall der Differenz `statistic(a) - statistic(b)`, beide Gruppen werden
+/// unabhängig voneinander neu gezogen; `None` bei weniger als zwei Werten in einer Gruppe
+pub fn bootstrap_difference(a: &[f64], b: &[f64], statistic: Statistic, config: &BootstrapConfig) -> Option<Interval> {
+    if a.len() < 2 || b.len() < 2 {
+        return None;
+    }
+    let estimate = statistic.compute(a) - statistic.compute(b);
+    let boots = replicates(config, |rng| {
+        let (mut sample_a, mut sample_b) = (Vec::with_capacity(a.len()), Vec::with_capacity(b.len()));
+        resample(a, rng, &mut sample_a);
+        resample(b, rng, &mut sample_b);
+        statistic.compute_in_place(&mut sample_a) - statistic.compute_in_place(&mut sample_b)
+    });
+    let jackknife = || {
+        let (stat_a, stat_b) = (statistic.compute(a), statistic.compute(b));
+        let jack_a = statistic.jackknife(a).into_iter().map(|v| v - stat_b);
+        jack_a.chain(statistic.jackknife(b).into_iter().map(|v| stat_a - v)).collect()
+    };
+    Some(interval(config, estimate, boots, jackknife))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "erwartet {expected}, erhalten {actual} (Toleranz {tolerance})"
+        );
+    }
+
+    /// Maus-Daten (Behandlungsgruppe) aus Efron & Tibshirani, "An Introduction to the Bootstrap", Tabelle 2.1
+    const MOUSE_TREATMENT: [f64; 7] = [94.0, 197.0, 16.0, 38.0, 99.0, 141.0, 23.0];
+
+    fn config(method: IntervalMethod, resamples: usize) -> BootstrapConfig {
+        BootstrapConfig { method, resamples, seed: 7, confidence: 0.95 }
+    }
+
+    #[test]
+    fn seed_fixes_result_independent_of_threads() {
+        let config = config(IntervalMethod::Bca, 999);
+        let replicate = |rng: &mut SplitMix64| {
+            let mut sample = Vec::new();
+            resample(&MOUSE_TREATMENT, rng, &mut sample);
+            Statistic::Median.compute_in_place(&mut sample)
+        };
+        let single = replicates_on(1, &config, replicate);
+        for threads in [2, 3, 8, 64] {
+            assert_eq!(replicates_on(threads, &config, replicate), single, "{threads} Threads");
+        }
+
+        let estimate = Statistic::Median.compute(&MOUSE_TREATMENT);
+        let jackknife = || Statistic::Median.jackknife(&MOUSE_TREATMENT);
+        let a = interval(&config, estimate, single.clone(), jackknife);
+        let b = interval(&config, estimate, replicates_on(5, &config, replicate), jackknife);
+        assert_eq!((a.lower, a.upper, a.std_error), (b.lower, b.upper, b.std_error));
+
+        let other_seed = BootstrapConfig { seed: 8, ..config };
+        assert_ne!(replicates_on(1, &other_seed, replicate), single);
+    }
+
+    #[test]
+    fn percentile_interval() {
+        // Perzentile von 1..=1000 mit linearer Interpolation (R: quantile(1:1000, c(0.025, 0.975), type = 7))
+        let boots = (1..=1000).map(f64::from).collect::<Vec<f64>>();
+        let result = interval(&config(IntervalMethod::Percentile, 1000), 500.5, boots, Vec::new);
+        assert_close(result.lower, 25.975, 1e-9);
+        assert_close(result.upper, 975.025, 1e-9);
+        assert_close(result.std_error, (1000.0_f64 * 1001.0 / 12.0).sqrt(), 1e-9);
+    }
+
+    #[test]
+    fn bca_levels_match_formula() {
+        // z0 = Φ⁻¹(0.4), a aus den Jackknife-Werten [1, 2, 3, 10]; Referenz mit Pythons statistics.NormalDist
+        let boots = (1..=100).map(f64::from).collect::<Vec<f64>>();
+        let (low, high) = bca_levels(40.5, &boots, &[1.0, 2.0, 3.0, 10.0], 0.025);
+        assert_close(low, 0.001_448_563_248_162_948_5, 1e-8);
+        assert_close(high, 0.892_029_529_431_499_3, 1e-8);
+
+        // Symmetrische Replikate und Jackknife-Werte: wie das Perzentil-Intervall
+        let (low, high) = bca_levels(50.5, &boots, &[1.0, 2.0, 3.0], 0.025);
+        assert_close(low, 0.025, 1e-9);
+        assert_close(high, 0.975, 1e-9);
+    }
+
+    #[test]
+    fn standard_error_of_mean() {
+        // Ideale Bootstrap-Schätzung des Standardfehlers: 23.36 (Efron & Tibshirani, Abschnitt 2.3)
+        let result = bootstrap_statistic(&MOUSE_TREATMENT, Statistic::Mean, &config(IntervalMethod::Percentile, 20_000))
+            .unwrap();
+        assert_close(result.estimate, 86.857_142_857_142_86, 1e-9);
+        assert_close(result.std_error, 23.36, 0.5);
+        assert!(result.lower < result.estimate && result.estimate < result.upper);
+
+        let bca = bootstrap_statistic(&MOUSE_TREATMENT, Statistic::Mean, &config(IntervalMethod::Bca, 20_000)).unwrap();
+        assert!(bca.lower < bca.estimate && bca.estimate < bca.upper);
+        assert!(bootstrap_statistic(&[1.0], Statistic::Mean, &config(IntervalMethod::Bca, 100)).is_none());
+    }
+}
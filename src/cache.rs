@@ -0,0 +1,150 @@
+// src/cache.rs
+
+use crate::validation::ValidationReport;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Dateiendung des Caches (Arrow IPC)
+pub const CACHE_EXTENSION: &str = "arrow";
+
+/// Version des Cache-Inhalts; erhöhen, sobald sich abgeleitete Spalten ändern
+pub const CACHE_SCHEMA_VERSION: u32 = 6;
+
+/// Stabiler Schlüssel (FNV-1a, 64 Bit) über Quelldatei und Konfiguration.
+/// Anders als `DefaultHasher` bleibt er über Rust-Versionen gleich.
+#[derive(Debug, Clone)]
+pub struct CacheKey {
+    hash: u64,
+}
+
+impl Default for CacheKey {
+    fn default() -> Self {
+        CacheKey { hash: 0xcbf2_9ce4_8422_2325 }
+    }
+}
+
+impl CacheKey {
+    pub fn add_bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        for byte in bytes {
+            self.hash ^= u64::from(*byte);
+            self.hash = self.hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        // Trenner, damit ("ab", "c") und ("a", "bc") verschiedene Schlüssel ergeben
+        self.hash ^= 0xff;
+        self.hash = self.hash.wrapping_mul(0x0100_0000_01b3);
+        self
+    }
+
+    pub fn add_str(&mut self, text: &str) -> &mut Self {
+        self.add_bytes(text.as_bytes())
+    }
+
+    /// Inhalt einer Datei einbeziehen; fehlende Dateien zählen als leer
+    pub fn add_file(&mut self, path: &str) -> std::io::Result<&mut Self> {
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(self.add_bytes(&bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(self.add_bytes(&[])),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn hex(&self) -> String {
+        format!("{:016x}", self.hash)
+    }
+}
+
+/// Begleitdatei des Caches mit Schlüssel, Validierungsbericht und angewendeten Schritten
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheMeta {
+    key: String,
+    report: ValidationReport,
+    #[serde(default)]
+    cleaning_steps: Vec<String>,
+}
+
+/// Inhalt eines aktuellen Caches
+pub struct CachedSurvey {
+    pub df: DataFrame,
+    /// Geladene, aber unbereinigte Daten
+    pub raw: DataFrame,
+    pub report: ValidationReport,
+    pub cleaning_steps: Vec<String>,
+}
+
+/// Pfad des Caches neben der Quelldatei (`cleaned_data.csv` -> `cleaned_data.arrow`)
+pub fn cache_path(source: &str) -> PathBuf {
+    Path::new(source).with_extension(CACHE_EXTENSION)
+}
+
+/// Pfad des Caches der unbereinigten Daten (`cleaned_data.raw.arrow`)
+fn raw_cache_path(source: &str) -> PathBuf {
+    Path::new(source).with_extension(format!("raw.{CACHE_EXTENSION}"))
+}
+
+fn meta_path(cache: &Path) -> PathBuf {
+    cache.with_extension(format!("{CACHE_EXTENSION}.json"))
+}
+
+/// Ist die Datei ein Cache (und kein CSV)?
+pub fn is_cache_file(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|ext| ext == CACHE_EXTENSION)
+}
+
+/// Arrow-IPC-Datei lesen. Der Bericht kommt aus der Begleitdatei, ohne sie gelten alle Zeilen als gültig.
+pub fn read_cache(path: &Path) -> PolarsResult<(DataFrame, ValidationReport)> {
+    let df = IpcReader::new(File::open(path)?).finish()?;
+    let report = read_meta(path)
+        .map(|meta| meta.report)
+        .unwrap_or_else(|| {
+            let mut report = ValidationReport::new(&path.display().to_string());
+            for _ in 0..df.height() {
+                report.record_valid();
+            }
+            report
+        });
+    Ok((df, report))
+}
+
+fn read_meta(cache: &Path) -> Option<CacheMeta> {
+    let file = File::open(meta_path(cache)).ok()?;
+    serde_json::from_reader(file).ok()
+}
+
+/// Cache laden, wenn er existiert und zum Schlüssel passt
+pub fn load_fresh(source: &str, key: &CacheKey) -> Option<CachedSurvey> {
+    let path = cache_path(source);
+    let meta = read_meta(&path)?;
+    if meta.key != key.hex() {
+        return None;
+    }
+    let read = |path: &Path| -> PolarsResult<DataFrame> { IpcReader::new(File::open(path)?).finish() };
+    match read(&path).and_then(|df| Ok((df, read(&raw_cache_path(source))?))) {
+        Ok((df, raw)) => Some(CachedSurvey {
+            df,
+            raw,
+            report: meta.report,
+            cleaning_steps: meta.cleaning_steps,
+        }),
+        Err(e) => {
+            println!("Cache {} nicht lesbar, lade neu: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// Aufbereitetes und unbereinigtes DataFrame samt Bericht als Cache neben die Quelldatei schreiben
+pub fn write_cache(source: &str, key: &CacheKey, survey: &mut CachedSurvey) -> PolarsResult<PathBuf> {
+    let path = cache_path(source);
+    IpcWriter::new(File::create(&path)?).finish(&mut survey.df)?;
+    IpcWriter::new(File::create(raw_cache_path(source))?).finish(&mut survey.raw)?;
+    let meta = CacheMeta {
+        key: key.hex(),
+        report: survey.report.clone(),
+        cleaning_steps: survey.cleaning_steps.clone(),
+    };
+    serde_json::to_writer(File::create(meta_path(&path))?, &meta)
+        .map_err(|e| polars_err!(ComputeError: "Cache-Metadaten nicht schreibbar: {}", e))?;
+    Ok(path)
+}
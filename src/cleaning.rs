@@ -0,0 +1,277 @@
+// src/cleaning.rs
+
+use crate::columns::{registry, Col};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+
+/// Ein einzelner, benannter Bereinigungsschritt.
+/// In JSON z. B. `{"op": "to_number", "column": "Age"}`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum CleaningStep {
+    /// Leerzeichen am Anfang und Ende entfernen
+    Trim { column: String },
+    /// Teilstring ersetzen, z. B. Dezimalkomma durch Punkt
+    ReplaceText { column: String, from: String, to: String },
+    /// In f64 umwandeln, nicht lesbare Werte werden null
+    ToNumber { column: String },
+    /// Fehlende Werte durch den Mittelwert der Spalte ersetzen
+    FillMean { column: String },
+    /// Fehlende Werte durch einen festen Text ersetzen
+    FillConstant { column: String, value: String },
+    /// Einzelne Werte umkodieren (alt -> neu)
+    Recode { column: String, mapping: Vec<(String, String)> },
+    /// Zeilen verwerfen, deren Wert außerhalb von [min, max] liegt (null bleibt erhalten)
+    DropOutside {
+        column: String,
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+    },
+}
+
+impl fmt::Display for CleaningStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CleaningStep::Trim { column } => write!(f, "trim '{column}'"),
+            CleaningStep::ReplaceText { column, from, to } => {
+                write!(f, "replace '{from}' -> '{to}' in '{column}'")
+            }
+            CleaningStep::ToNumber { column } => write!(f, "to_number '{column}'"),
+            CleaningStep::FillMean { column } => write!(f, "fill_mean '{column}'"),
+            CleaningStep::FillConstant { column, value } => {
+                write!(f, "fill_constant '{column}' = '{value}'")
+            }
+            CleaningStep::Recode { column, mapping } => {
+                write!(f, "recode '{column}' ({} Werte)", mapping.len())
+            }
+            CleaningStep::DropOutside { column, min, max } => {
+                write!(f, "drop_outside '{column}' [{min:?}, {max:?}]")
+            }
+        }
+    }
+}
+
+/// Protokoll eines ausgeführten Schritts
+#[derive(Debug, Clone, Serialize)]
+pub struct StepLog {
+    pub step: String,
+    pub rows_before: usize,
+    pub rows_after: usize,
+    /// Geänderte Zellen bzw. verworfene Zeilen
+    pub changed: usize,
+}
+
+/// Protokoll der gesamten Pipeline
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CleaningLog {
+    pub steps: Vec<StepLog>,
+}
+
+impl CleaningLog {
+    pub fn print_summary(&self) {
+        println!("=== Bereinigung ===");
+        for log in &self.steps {
+            println!(
+                "{:<60} Zeilen {} -> {}, geändert: {}",
+                log.step, log.rows_before, log.rows_after, log.changed
+            );
+        }
+    }
+}
+
+/// Geordnete Liste von Bereinigungsschritten
+#[derive(Debug, Clone, Deserialize)]
+pub struct CleaningPipeline {
+    pub steps: Vec<CleaningStep>,
+}
+
+impl CleaningPipeline {
+    /// Pipeline aus einer JSON-Datei `{"steps": [...]}` lesen
+    pub fn from_json_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_reader(File::open(path)?)?)
+    }
+
+    /// Schritte, mit denen aus IT_Salary_Survey_2020.csv die Datei cleaned_data.csv entsteht
+    pub fn survey_2020() -> Self {
+        let age = Col::Age.key().to_string();
+        let experience = Col::Experience.key().to_string();
+        let experience_germany = Col::ExperienceGermany.key().to_string();
+        let salary = Col::Salary.key().to_string();
+        let shorter_week = Col::ShorterWorkWeek.key().to_string();
+
+        CleaningPipeline {
+            steps: vec![
+                CleaningStep::ToNumber { column: age.clone() },
+                CleaningStep::FillMean { column: age },
+                CleaningStep::ToNumber { column: experience.clone() },
+                CleaningStep::FillMean { column: experience.clone() },
+                CleaningStep::ReplaceText {
+                    column: experience_germany.clone(),
+                    from: ",".to_string(),
+                    to: ".".to_string(),
+                },
+                CleaningStep::ToNumber { column: experience_germany.clone() },
+                CleaningStep::FillMean { column: experience_germany },
+                CleaningStep::ToNumber { column: salary.clone() },
+                CleaningStep::ToNumber { column: shorter_week },
+                CleaningStep::FillConstant {
+                    column: Col::Gender.key().to_string(),
+                    value: "Unknown".to_string(),
+                },
+                CleaningStep::DropOutside {
+                    column: salary,
+                    min: None,
+                    max: Some(500_000.0),
+                },
+                CleaningStep::DropOutside {
+                    column: experience,
+                    min: None,
+                    max: Some(50.0),
+                },
+            ],
+        }
+    }
+
+    /// Alle Schritte der Reihe nach auf das DataFrame anwenden
+    pub fn apply(&self, df: &mut DataFrame) -> PolarsResult<CleaningLog> {
+        let mut log = CleaningLog::default();
+        for step in &self.steps {
+            let rows_before = df.height();
+            let changed = apply_step(df, step)?;
+            log.steps.push(StepLog {
+                step: step.to_string(),
+                rows_before,
+                rows_after: df.height(),
+                changed,
+            });
+        }
+        Ok(log)
+    }
+}
+
+impl CleaningStep {
+    /// Spalte, auf die der Schritt wirkt (Schlüssel, Alias oder Spaltenname)
+    fn column(&self) -> &str {
+        match self {
+            CleaningStep::Trim { column }
+            | CleaningStep::ReplaceText { column, .. }
+            | CleaningStep::ToNumber { column }
+            | CleaningStep::FillMean { column }
+            | CleaningStep::FillConstant { column, .. }
+            | CleaningStep::Recode { column, .. }
+            | CleaningStep::DropOutside { column, .. } => column,
+        }
+    }
+}
+
+/// Einen Schritt anwenden, Rückgabe ist die Anzahl der Änderungen
+fn apply_step(df: &mut DataFrame, step: &CleaningStep) -> PolarsResult<usize> {
+    let column = registry().resolve_name(df, step.column())?;
+    let column = column.as_str();
+    match step {
+        CleaningStep::Trim { .. } => map_text(df, column, |v| v.trim().to_string()),
+        CleaningStep::ReplaceText { from, to, .. } => {
+            map_text(df, column, |v| v.replace(from.as_str(), to))
+        }
+        CleaningStep::ToNumber { .. } => {
+            let series = df.column(column)?.as_materialized_series().clone();
+            let converted = series.cast(&DataType::Float64)?;
+            let changed = converted.null_count() - series.null_count();
+            df.replace(column, converted)?;
+            Ok(changed)
+        }
+        CleaningStep::FillMean { .. } => {
+            let series = df.column(column)?.as_materialized_series().clone();
+            let filled = series.fill_null(FillNullStrategy::Mean)?;
+            let changed = series.null_count() - filled.null_count();
+            df.replace(column, filled)?;
+            Ok(changed)
+        }
+        CleaningStep::FillConstant { value, .. } => {
+            let series = df.column(column)?.as_materialized_series().clone();
+            if series.dtype() != &DataType::String {
+                return Ok(0);
+            }
+            let changed = series.null_count();
+            let filled = series
+                .str()?
+                .into_iter()
+                .map(|v| Some(v.unwrap_or(value)))
+                .collect::<StringChunked>()
+                .with_name(column.into());
+            df.replace(column, filled.into_series())?;
+            Ok(changed)
+        }
+        CleaningStep::Recode { mapping, .. } => map_text(df, column, |v| {
+            mapping
+                .iter()
+                .find(|(from, _)| from == v)
+                .map(|(_, to)| to.clone())
+                .unwrap_or_else(|| v.to_string())
+        }),
+        CleaningStep::DropOutside { min, max, .. } => {
+            let values = df.column(column)?.cast(&DataType::Float64)?;
+            let mask = values
+                .f64()?
+                .into_iter()
+                .map(|v| match v {
+                    Some(v) => min.is_none_or(|lo| v >= lo) && max.is_none_or(|hi| v <= hi),
+                    None => true,
+                })
+                .collect::<BooleanChunked>();
+            let before = df.height();
+            *df = df.filter(&mask)?;
+            Ok(before - df.height())
+        }
+    }
+}
+
+/// Textspalte Wert für Wert umschreiben und geänderte Zellen zählen.
+/// Bereits typisierte Spalten (z. B. aus `load_data`) bleiben unverändert.
+fn map_text(df: &mut DataFrame, column: &str, f: impl Fn(&str) -> String) -> PolarsResult<usize> {
+    if df.column(column)?.dtype() != &DataType::String {
+        return Ok(0);
+    }
+    let ca = df.column(column)?.str()?.clone();
+    let mut changed = 0;
+    let mapped = ca
+        .into_iter()
+        .map(|v| {
+            v.map(|v| {
+                let new = f(v);
+                if new != v {
+                    changed += 1;
+                }
+                new
+            })
+        })
+        .collect::<StringChunked>()
+        .with_name(column.into());
+    df.replace(column, mapped.into_series())?;
+    Ok(changed)
+}
+
+/// Rohdatei einlesen (alle Spalten als Text), bereinigen und als CSV plus JSON-Protokoll schreiben
+pub fn clean_raw_survey(
+    raw_path: &str,
+    output_path: &str,
+    log_path: &str,
+    pipeline: &CleaningPipeline,
+) -> Result<CleaningLog, Box<dyn std::error::Error>> {
+    let mut df = CsvReadOptions::default()
+        .with_has_header(true)
+        .with_infer_schema_length(Some(0))
+        .try_into_reader_with_file_path(Some(raw_path.into()))?
+        .finish()?;
+
+    let log = pipeline.apply(&mut df)?;
+
+    let mut file = File::create(output_path)?;
+    CsvWriter::new(&mut file).finish(&mut df)?;
+    serde_json::to_writer_pretty(File::create(log_path)?, &log)?;
+    Ok(log)
+}
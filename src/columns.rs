@@ -0,0 +1,335 @@
+// src/columns.rs
+
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::fs::File;
+use std::sync::OnceLock;
+
+/// Fachliche Spalten der Umfrage mit kurzem, stabilem Schlüssel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Col {
+    Timestamp,
+    Age,
+    Gender,
+    City,
+    Position,
+    Experience,
+    ExperienceGermany,
+    Seniority,
+    MainTech,
+    OtherTechs,
+    Salary,
+    Bonus,
+    SalaryLastYear,
+    BonusLastYear,
+    VacationDays,
+    EmploymentStatus,
+    ContractDuration,
+    WorkLanguage,
+    CompanySize,
+    CompanyType,
+    LostJobCovid,
+    ShorterWorkWeek,
+    WfhSupport,
+}
+
+impl Col {
+    /// Alle Spalten in der Reihenfolge der Umfrage 2020
+    pub const ALL: [Col; 23] = [
+        Col::Timestamp,
+        Col::Age,
+        Col::Gender,
+        Col::City,
+        Col::Position,
+        Col::Experience,
+        Col::ExperienceGermany,
+        Col::Seniority,
+        Col::MainTech,
+        Col::OtherTechs,
+        Col::Salary,
+        Col::Bonus,
+        Col::SalaryLastYear,
+        Col::BonusLastYear,
+        Col::VacationDays,
+        Col::EmploymentStatus,
+        Col::ContractDuration,
+        Col::WorkLanguage,
+        Col::CompanySize,
+        Col::CompanyType,
+        Col::LostJobCovid,
+        Col::ShorterWorkWeek,
+        Col::WfhSupport,
+    ];
+
+    /// Kurzer Schlüssel, z. B. für Query-Parameter und Alias-Dateien
+    pub fn key(self) -> &'static str {
+        match self {
+            Col::Timestamp => "timestamp",
+            Col::Age => "age",
+            Col::Gender => "gender",
+            Col::City => "city",
+            Col::Position => "position",
+            Col::Experience => "experience",
+            Col::ExperienceGermany => "experience_germany",
+            Col::Seniority => "seniority",
+            Col::MainTech => "main_tech",
+            Col::OtherTechs => "other_techs",
+            Col::Salary => "salary",
+            Col::Bonus => "bonus",
+            Col::SalaryLastYear => "salary_last_year",
+            Col::BonusLastYear => "bonus_last_year",
+            Col::VacationDays => "vacation_days",
+            Col::EmploymentStatus => "employment_status",
+            Col::ContractDuration => "contract_duration",
+            Col::WorkLanguage => "work_language",
+            Col::CompanySize => "company_size",
+            Col::CompanyType => "company_type",
+            Col::LostJobCovid => "lost_job_covid",
+            Col::ShorterWorkWeek => "shorter_work_week",
+            Col::WfhSupport => "wfh_support",
+        }
+    }
+
+    /// Spaltenname, wie ihn `SurveyRecord` und das interne DataFrame verwenden
+    pub fn header(self) -> &'static str {
+        match self {
+            Col::Timestamp => "Timestamp",
+            Col::Age => "Age",
+            Col::Gender => "Gender",
+            Col::City => "City",
+            Col::Position => "Position",
+            Col::Experience => "Total years of experience",
+            Col::ExperienceGermany => "Years of experience in Germany",
+            Col::Seniority => "Seniority level",
+            Col::MainTech => "Your main technology / programming language",
+            Col::OtherTechs => "Other technologies/programming languages you use often",
+            Col::Salary => "Yearly brutto salary (without bonus and stocks) in EUR",
+            Col::Bonus => "Yearly bonus + stocks in EUR",
+            Col::SalaryLastYear => "Annual brutto salary (without bonus and stocks) one year ago. Only answer if staying in the same country",
+            Col::BonusLastYear => "Annual bonus+stocks one year ago. Only answer if staying in same country",
+            Col::VacationDays => "Number of vacation days",
+            Col::EmploymentStatus => "Employment status",
+            Col::ContractDuration => "Сontract duration",
+            Col::WorkLanguage => "Main language at work",
+            Col::CompanySize => "Company size",
+            Col::CompanyType => "Company type",
+            Col::LostJobCovid => "Have you lost your job due to the coronavirus outbreak?",
+            Col::ShorterWorkWeek => "Have you been forced to have a shorter working week (Kurzarbeit)? If yes, how many hours per week",
+            Col::WfhSupport => "Have you received additional monetary support from your employer due to Work From Home? If yes, how much in 2020 in EUR",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Col> {
+        Col::ALL.into_iter().find(|c| c.key() == key.trim())
+    }
+}
+
+/// Kyrillische und griechische Buchstaben, die wie lateinische aussehen
+fn fold_homoglyph(c: char) -> char {
+    match c {
+        'А' | 'а' | 'Α' | 'α' => 'a',
+        'В' | 'в' | 'Β' => 'b',
+        'С' | 'с' | 'Ϲ' | 'ϲ' => 'c',
+        'Е' | 'е' | 'Ε' | 'ε' => 'e',
+        'Н' | 'н' | 'Η' => 'h',
+        'І' | 'і' | 'Ι' | 'ι' => 'i',
+        'Ј' | 'ј' => 'j',
+        'К' | 'к' | 'Κ' | 'κ' => 'k',
+        'М' | 'м' | 'Μ' => 'm',
+        'О' | 'о' | 'Ο' | 'ο' => 'o',
+        'Р' | 'р' | 'Ρ' | 'ρ' => 'p',
+        'Ѕ' | 'ѕ' => 's',
+        'Т' | 'т' | 'Τ' | 'τ' => 't',
+        'Х' | 'х' | 'Χ' | 'χ' => 'x',
+        'У' | 'у' | 'Υ' => 'y',
+        _ => c,
+    }
+}
+
+/// Spaltennamen vergleichbar machen: Homoglyphen falten, Kleinschreibung,
+/// Leerraum am Rand entfernen und innen zu einem Leerzeichen zusammenfassen
+pub fn normalize_header(name: &str) -> String {
+    name.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .map(fold_homoglyph)
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Ordnet jedem `Col` die bekannten Schreibweisen seines Spaltennamens zu
+#[derive(Debug, Clone)]
+pub struct ColumnRegistry {
+    /// Normalisierte Alias-Namen je Spalte
+    aliases: HashMap<Col, Vec<String>>,
+}
+
+impl Default for ColumnRegistry {
+    fn default() -> Self {
+        let aliases = Col::ALL
+            .into_iter()
+            .map(|c| (c, vec![normalize_header(c.header()), normalize_header(c.key())]))
+            .collect();
+        ColumnRegistry { aliases }
+    }
+}
+
+impl ColumnRegistry {
+    /// Zusätzliche Schreibweise für eine Spalte registrieren
+    pub fn add_alias(&mut self, col: Col, alias: &str) {
+        let alias = normalize_header(alias);
+        let entry = self.aliases.entry(col).or_default();
+        if !entry.contains(&alias) {
+            entry.push(alias);
+        }
+    }
+
+    /// Aliase aus einer JSON-Datei `{"salary": ["Gehalt", ...], ...}` ergänzen
+    pub fn load_aliases(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let file: HashMap<String, Vec<String>> = serde_json::from_reader(File::open(path)?)?;
+        for (key, aliases) in file {
+            let col = Col::from_key(&key).ok_or_else(|| format!("Unbekannter Spaltenschlüssel '{key}' in {path}"))?;
+            for alias in aliases {
+                self.add_alias(col, &alias);
+            }
+        }
+        Ok(())
+    }
+
+    /// Zu einem beliebigen Spaltennamen die fachliche Spalte bestimmen
+    pub fn identify(&self, header: &str) -> Option<Col> {
+        let normalized = normalize_header(header);
+        Col::ALL
+            .into_iter()
+            .find(|c| self.aliases.get(c).is_some_and(|a| a.contains(&normalized)))
+    }
+
+    /// Tatsächlichen Spaltennamen von `col` in `df` suchen
+    pub fn resolve(&self, df: &DataFrame, col: Col) -> PolarsResult<String> {
+        df.get_column_names()
+            .into_iter()
+            .find(|name| self.identify(name) == Some(col))
+            .map(|name| name.to_string())
+            .ok_or_else(|| {
+                polars_err!(
+                    ColumnNotFound: "Spalte '{}' ({}) nicht gefunden; vorhandene Spalten: {:?}",
+                    col.key(), col.header(), df.get_column_names()
+                )
+            })
+    }
+
+    /// Spalte `col` aus `df` holen
+    pub fn column<'a>(&self, df: &'a DataFrame, col: Col) -> PolarsResult<&'a Column> {
+        let name = self.resolve(df, col)?;
+        df.column(&name)
+    }
+
+    /// Name aus einer Anfrage oder Konfiguration auflösen: Schlüssel, Alias
+    /// oder ein beliebiger (auch abgeleiteter) Spaltenname
+    pub fn resolve_name(&self, df: &DataFrame, name: &str) -> PolarsResult<String> {
+        if let Some(col) = Col::from_key(name).or_else(|| self.identify(name)) {
+            return self.resolve(df, col);
+        }
+        let normalized = normalize_header(name);
+        df.get_column_names()
+            .into_iter()
+            .find(|c| normalize_header(c) == normalized)
+            .map(|c| c.to_string())
+            .ok_or_else(|| {
+                polars_err!(
+                    ColumnNotFound: "Spalte '{}' nicht gefunden; vorhandene Spalten: {:?}",
+                    name, df.get_column_names()
+                )
+            })
+    }
+}
+
+static REGISTRY: OnceLock<ColumnRegistry> = OnceLock::new();
+
+/// Registry einmalig beim Start festlegen (z. B. mit Aliasen aus einer Datei)
+pub fn init_registry(registry: ColumnRegistry) {
+    // Ein zweiter Aufruf ändert nichts, die erste Registry bleibt gültig
+    let _ = REGISTRY.set(registry);
+}
+
+/// Die prozessweite Registry
+pub fn registry() -> &'static ColumnRegistry {
+    REGISTRY.get_or_init(ColumnRegistry::default)
+}
+
+/// Kurzform für `registry().column(df, col)`
+pub fn column(df: &DataFrame, col: Col) -> PolarsResult<&Column> {
+    registry().column(df, col)
+}
+
+/// Kurzform für `registry().resolve(df, col)`
+pub fn resolve(df: &DataFrame, col: Col) -> PolarsResult<String> {
+    registry().resolve(df, col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_whitespace_case_and_homoglyphs() {
+        assert_eq!(normalize_header("  Position \t"), "position");
+        assert_eq!(normalize_header("Total   years OF\nexperience"), "total years of experience");
+        // Kyrillisches "С" am Anfang wie in der Umfrage 2020
+        assert_eq!(normalize_header("Сontract duration"), "contract duration");
+        assert_eq!(normalize_header("ΑGE"), "age");
+    }
+
+    #[test]
+    fn identifies_headers_keys_and_aliases() {
+        let mut registry = ColumnRegistry::default();
+        assert_eq!(registry.identify("Position "), Some(Col::Position));
+        assert_eq!(registry.identify("contract duration"), Some(Col::ContractDuration));
+        assert_eq!(registry.identify("SENIORITY LEVEL"), Some(Col::Seniority));
+        assert_eq!(registry.identify("salary"), Some(Col::Salary));
+        assert_eq!(registry.identify("Gehalt"), None);
+
+        registry.add_alias(Col::Salary, "  Gehalt ");
+        assert_eq!(registry.identify("gehalt"), Some(Col::Salary));
+        assert_eq!(registry.identify("Lieblingsfarbe"), None);
+    }
+
+    #[test]
+    fn loads_aliases_from_json() {
+        let path = std::env::temp_dir().join(format!("column_aliases_{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"salary": ["Gehalt"], "city": ["Stadt", "Ort"]}"#).unwrap();
+        let mut registry = ColumnRegistry::default();
+        registry.load_aliases(path.to_str().unwrap()).unwrap();
+        assert_eq!(registry.identify("Gehalt"), Some(Col::Salary));
+        assert_eq!(registry.identify("ort"), Some(Col::City));
+
+        std::fs::write(&path, r#"{"lieblingsfarbe": ["Farbe"]}"#).unwrap();
+        let error = registry.load_aliases(path.to_str().unwrap()).unwrap_err();
+        assert!(error.to_string().contains("lieblingsfarbe"), "{error}");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn resolves_columns_in_a_frame() {
+        let mut registry = ColumnRegistry::default();
+        registry.add_alias(Col::Salary, "Gehalt");
+        let df = df!(
+            "Gehalt" => [60_000.0],
+            "Сontract duration" => ["Unlimited contract"],
+            "city_normalized" => ["Berlin"],
+        )
+        .unwrap();
+
+        assert_eq!(registry.resolve(&df, Col::Salary).unwrap(), "Gehalt");
+        assert_eq!(registry.resolve(&df, Col::ContractDuration).unwrap(), "Сontract duration");
+        assert_eq!(registry.resolve_name(&df, "salary").unwrap(), "Gehalt");
+        assert_eq!(registry.resolve_name(&df, "City_Normalized").unwrap(), "city_normalized");
+
+        let missing = registry.resolve(&df, Col::Age).unwrap_err().to_string();
+        assert!(missing.contains("'age'") && missing.contains("Gehalt"), "{missing}");
+        assert!(registry.resolve_name(&df, "unbekannt").is_err());
+    }
+}
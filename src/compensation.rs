@@ -0,0 +1,123 @@
+// src/compensation.rs
+
+use crate::columns::{column, registry, resolve, Col};
+use crate::error::{AppError, AppResult};
+use polars::prelude::*;
+
+/// Abgeleitete Spalte mit Grundgehalt plus Bonus und Aktien in EUR
+pub const TOTAL_COMPENSATION_COL: &str = "total_compensation";
+/// Abgeleitete Spalte mit dem Anteil von Bonus und Aktien an der Gesamtvergütung (0 bis 1)
+pub const BONUS_SHARE_COL: &str = "bonus_share";
+
+/// Spalten `total_compensation` und `bonus_share` anhängen. Gehalt und Bonus werden dafür als
+/// Zahl gelesen (nicht lesbarer Text wie "depends" zählt als fehlend). Ein fehlender Bonus
+/// zählt für die Gesamtvergütung als 0, der Bonusanteil bleibt dann leer.
+pub fn add_compensation_columns(df: &mut DataFrame) -> PolarsResult<()> {
+    let salary = column(df, Col::Salary)?.cast(&DataType::Float64)?;
+    let bonus = column(df, Col::Bonus)?.cast(&DataType::Float64)?;
+
+    let (total, share): (Vec<Option<f64>>, Vec<Option<f64>>) = salary
+        .f64()?
+        .into_iter()
+        .zip(bonus.f64()?)
+        .map(|(salary, bonus)| {
+            let total = salary.map(|s| s + bonus.unwrap_or(0.0));
+            let share = match (bonus, total) {
+                (Some(bonus), Some(total)) if total > 0.0 => Some(bonus / total),
+                _ => None,
+            };
+            (total, share)
+        })
+        .unzip();
+
+    df.with_column(Column::new(TOTAL_COMPENSATION_COL.into(), total))?;
+    df.with_column(Column::new(BONUS_SHARE_COL.into(), share))?;
+    Ok(())
+}
+
+/// Vergütungsbestandteil für `metric=base|bonus|total`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Metric {
+    /// Grundgehalt ohne Bonus und Aktien
+    #[default]
+    Base,
+    /// Bonus und Aktien
+    Bonus,
+    /// Grundgehalt plus Bonus und Aktien
+    Total,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 3] = [Metric::Base, Metric::Bonus, Metric::Total];
+
+    pub fn parse(name: &str) -> Result<Metric, String> {
+        match name.trim().to_lowercase().as_str() {
+            "base" | "salary" => Ok(Metric::Base),
+            "bonus" => Ok(Metric::Bonus),
+            "total" | "total_compensation" => Ok(Metric::Total),
+            other => Err(format!("Kennzahl '{other}' ist unbekannt (base, bonus, total)")),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Metric::Base => "base",
+            Metric::Bonus => "bonus",
+            Metric::Total => "total",
+        }
+    }
+
+    /// Schlüssel der Kennzahl in JSON-Antworten (`salary` wie bisher für das Grundgehalt)
+    pub fn key(&self) -> &'static str {
+        match self {
+            Metric::Base => Col::Salary.key(),
+            Metric::Bonus => Col::Bonus.key(),
+            Metric::Total => TOTAL_COMPENSATION_COL,
+        }
+    }
+
+    /// Spaltenname in `df`
+    pub fn column(&self, df: &DataFrame) -> AppResult<String> {
+        match self {
+            Metric::Base => Ok(resolve(df, Col::Salary)?),
+            Metric::Bonus => Ok(resolve(df, Col::Bonus)?),
+            Metric::Total => Ok(registry().resolve_name(df, TOTAL_COMPENSATION_COL)?),
+        }
+    }
+}
+
+/// `metric`-Parameter aus einer Anfrage; ohne Angabe das Grundgehalt
+pub fn parse_metric(name: Option<&str>) -> AppResult<Metric> {
+    name.map(Metric::parse)
+        .transpose()
+        .map_err(AppError::InvalidParameter)
+        .map(Option::unwrap_or_default)
+}
+
+/// Spalte zu `base`, `bonus` oder `total` oder zu einem beliebigen anderen Spaltennamen
+pub fn resolve_metric(df: &DataFrame, name: &str) -> AppResult<String> {
+    match Metric::parse(name) {
+        Ok(metric) => metric.column(df),
+        Err(_) => Ok(registry().resolve_name(df, name)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_bonus_counts_as_zero_in_total() {
+        let mut df = df!(
+            Col::Salary.header() => [Some(60_000.0), Some(80_000.0), None],
+            Col::Bonus.header() => [Some(20_000.0), None, Some(5_000.0)],
+        )
+        .unwrap();
+        add_compensation_columns(&mut df).unwrap();
+
+        let total = df.column(TOTAL_COMPENSATION_COL).unwrap().f64().unwrap().to_vec();
+        assert_eq!(total, vec![Some(80_000.0), Some(80_000.0), None]);
+        let share = df.column(BONUS_SHARE_COL).unwrap().f64().unwrap().to_vec();
+        assert_eq!(share, vec![Some(0.25), None, None]);
+    }
+}
@@ -0,0 +1,263 @@
+// src/correlations.rs
+
+use crate::columns::registry;
+use crate::compensation::resolve_metric;
+use crate::data_analysis::{ensure_rows, numeric_series, OTHER_GROUP};
+use crate::error::{AppError, AppResult};
+use crate::inference::{chi_squared_survival, ranks, t_two_sided_p};
+use polars::prelude::*;
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Standard-Spalten für Pearson und Spearman
+pub const DEFAULT_NUMERIC_COLUMNS: [&str; 6] =
+    ["experience", "age", "experience_germany", "vacation_days", "salary", "bonus"];
+
+/// Standard-Spalten für Cramérs V und η
+pub const DEFAULT_CATEGORICAL_COLUMNS: [&str; 4] = ["gender", "seniority", "company_type", "company_size"];
+
+/// Unter so vielen gemeinsamen Werten bleibt ein Koeffizient leer
+pub const MIN_PAIRS: usize = 3;
+
+/// Pearson-Korrelation zweier gleich langer Reihen, `None` ohne Streuung
+fn pearson(x: &[f64], y: &[f64]) -> Option<f64> {
+    let n = x.len() as f64;
+    let mean_x = x.iter().sum::<f64>() / n;
+    let mean_y = y.iter().sum::<f64>() / n;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (a, b) in x.iter().zip(y) {
+        cov += (a - mean_x) * (b - mean_y);
+        var_x += (a - mean_x).powi(2);
+        var_y += (b - mean_y).powi(2);
+    }
+    (var_x > 0.0 && var_y > 0.0).then(|| (cov / (var_x * var_y).sqrt()).clamp(-1.0, 1.0))
+}
+
+/// Spearman-Korrelation: Pearson der (bei Gleichstand gemittelten) Ränge
+fn spearman(x: &[f64], y: &[f64]) -> Option<f64> {
+    pearson(&ranks(x).0, &ranks(y).0)
+}
+
+/// Zweiseitiger p-Wert für H0: ρ = 0 (t-Verteilung mit n - 2 Freiheitsgraden)
+fn correlation_p_value(r: f64, n: usize) -> f64 {
+    if r.abs() >= 1.0 {
+        return 0.0;
+    }
+    let df = n as f64 - 2.0;
+    t_two_sided_p(r * (df / (1.0 - r * r)).sqrt(), df)
+}
+
+/// Cramérs V der Kontingenztafel zweier kategorialer Reihen, mit p-Wert des Chi²-Tests
+fn cramers_v(x: &[&str], y: &[&str]) -> Option<(f64, f64)> {
+    let index = |values: &[&str]| {
+        let mut levels = HashMap::<String, usize>::new();
+        let codes = values
+            .iter()
+            .map(|v| {
+                let next = levels.len();
+                *levels.entry(v.to_string()).or_insert(next)
+            })
+            .collect::<Vec<usize>>();
+        (codes, levels.len())
+    };
+    let (codes_x, rows) = index(x);
+    let (codes_y, cols) = index(y);
+    if rows < 2 || cols < 2 {
+        return None;
+    }
+
+    let mut table = vec![vec![0.0; cols]; rows];
+    for (&i, &j) in codes_x.iter().zip(&codes_y) {
+        table[i][j] += 1.0;
+    }
+    let n = x.len() as f64;
+    let row_sums = table.iter().map(|row| row.iter().sum::<f64>()).collect::<Vec<f64>>();
+    let col_sums = (0..cols).map(|j| table.iter().map(|row| row[j]).sum::<f64>()).collect::<Vec<f64>>();
+    let chi_squared = (0..rows)
+        .flat_map(|i| (0..cols).map(move |j| (i, j)))
+        .map(|(i, j)| {
+            let expected = row_sums[i] * col_sums[j] / n;
+            (table[i][j] - expected).powi(2) / expected
+        })
+        .sum::<f64>();
+
+    let v = (chi_squared / (n * (rows.min(cols) - 1) as f64)).sqrt().min(1.0);
+    let df = ((rows - 1) * (cols - 1)) as f64;
+    Some((v, chi_squared_survival(chi_squared, df)))
+}
+
+/// Korrelationsverhältnis η: Anteil der Streuung von `values`, der durch die Kategorien erklärt wird (Wurzel)
+fn correlation_ratio(categories: &[&str], values: &[f64]) -> Option<f64> {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let total = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>();
+    if total == 0.0 {
+        return None;
+    }
+    let mut groups = HashMap::<&str, (f64, f64)>::new();
+    for (category, value) in categories.iter().zip(values) {
+        let entry = groups.entry(category).or_default();
+        entry.0 += 1.0;
+        entry.1 += value;
+    }
+    let between = groups
+        .values()
+        .map(|(count, sum)| count * (sum / count - mean).powi(2))
+        .sum::<f64>();
+    Some((between / total).sqrt().min(1.0))
+}
+
+/// Kategoriale Spalte als Text; seltene Werte (weniger als `min_count`) werden zu "other"
+fn categories(df: &DataFrame, name: &str, min_count: usize) -> AppResult<Vec<Option<String>>> {
+    let column = df.column(name)?;
+    if column.dtype().is_nested() {
+        return Err(AppError::wrong_type(name, "einzelne Werte", column.dtype()));
+    }
+    let text = column.cast(&DataType::String)?;
+    let text = text.str()?;
+    let mut counts = HashMap::<&str, usize>::new();
+    for value in text.into_iter().flatten() {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    Ok(text
+        .into_iter()
+        .map(|value| {
+            value.map(|v| if counts[v] >= min_count { v.to_string() } else { OTHER_GROUP.to_string() })
+        })
+        .collect())
+}
+
+/// Koeffizient, Anzahl gemeinsamer Werte und p-Wert als JSON; ohne Wert `null`
+fn cell(value: Option<(f64, f64)>, n: usize) -> serde_json::Value {
+    match value {
+        Some((coefficient, p_value)) => json!({ "value": coefficient, "n": n, "p_value": p_value }),
+        None => json!({ "value": null, "n": n, "p_value": null }),
+    }
+}
+
+/// Pearson- und Spearman-Matrix der numerischen Spalten, Cramérs V der kategorialen Spalten
+/// und η je kategorialer und numerischer Spalte. Jedes Paar nutzt alle Zeilen, in denen beide
+/// Werte vorhanden sind; Kategorien mit weniger als `min_count` Antworten werden zu "other".
+pub fn calculate_correlations(
+    df: &DataFrame,
+    numeric: &[String],
+    categorical: &[String],
+    min_count: usize,
+) -> AppResult<serde_json::Value> {
+    ensure_rows(df)?;
+    let numeric_names = numeric.iter().map(|name| resolve_metric(df, name)).collect::<AppResult<Vec<String>>>()?;
+    let categorical_names = categorical
+        .iter()
+        .map(|name| Ok(registry().resolve_name(df, name)?))
+        .collect::<AppResult<Vec<String>>>()?;
+
+    let numeric_values = numeric_names
+        .iter()
+        .map(|name| Ok(numeric_series(df, name)?.into_iter().collect::<Vec<Option<f64>>>()))
+        .collect::<AppResult<Vec<_>>>()?;
+    let categorical_values = categorical_names
+        .iter()
+        .map(|name| categories(df, name, min_count))
+        .collect::<AppResult<Vec<_>>>()?;
+
+    let numeric_pair = |a: &[Option<f64>], b: &[Option<f64>]| -> (Vec<f64>, Vec<f64>) {
+        a.iter().zip(b).filter_map(|(x, y)| Some(((*x)?, (*y)?))).unzip()
+    };
+
+    let mut pearson_matrix = Vec::new();
+    let mut spearman_matrix = Vec::new();
+    for a in &numeric_values {
+        let mut pearson_row = Vec::new();
+        let mut spearman_row = Vec::new();
+        for b in &numeric_values {
+            let (x, y) = numeric_pair(a, b);
+            let n = x.len();
+            let enough = n >= MIN_PAIRS;
+            let with_p = |r: Option<f64>| r.map(|r| (r, correlation_p_value(r, n)));
+            pearson_row.push(cell(enough.then(|| with_p(pearson(&x, &y))).flatten(), n));
+            spearman_row.push(cell(enough.then(|| with_p(spearman(&x, &y))).flatten(), n));
+        }
+        pearson_matrix.push(pearson_row);
+        spearman_matrix.push(spearman_row);
+    }
+
+    let cramers_matrix = categorical_values
+        .iter()
+        .map(|a| {
+            categorical_values
+                .iter()
+                .map(|b| {
+                    let (x, y): (Vec<&str>, Vec<&str>) = a
+                        .iter()
+                        .zip(b)
+                        .filter_map(|(x, y)| Some((x.as_deref()?, y.as_deref()?)))
+                        .unzip();
+                    let n = x.len();
+                    cell((n >= MIN_PAIRS).then(|| cramers_v(&x, &y)).flatten(), n)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let eta_matrix = categorical_values
+        .iter()
+        .map(|a| {
+            numeric_values
+                .iter()
+                .map(|b| {
+                    let (x, y): (Vec<&str>, Vec<f64>) = a
+                        .iter()
+                        .zip(b)
+                        .filter_map(|(x, y)| Some((x.as_deref()?, (*y)?)))
+                        .unzip();
+                    let n = x.len();
+                    let eta = (n >= MIN_PAIRS).then(|| correlation_ratio(&x, &y)).flatten();
+                    json!({ "value": eta, "n": n })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    Ok(json!({
+        "numeric": numeric_names,
+        "categorical": categorical_names,
+        "min_count": min_count,
+        "pearson": pearson_matrix,
+        "spearman": spearman_matrix,
+        "cramers_v": cramers_matrix,
+        "eta": eta_matrix,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "erwartet {expected}, erhalten {actual} (Toleranz {tolerance})"
+        );
+    }
+
+    #[test]
+    fn pearson_and_spearman() {
+        let x = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let y = [2.0, 4.0, 5.0, 4.0, 5.0];
+        // Referenz: R cor(x, y) = 0.7745967, cor(x, y, method = "spearman") = 0.7378648
+        assert_close(pearson(&x, &y).unwrap(), 0.774_596_669_241_483_4, 1e-12);
+        assert_close(spearman(&x, &y).unwrap(), 0.737_864_787_372_622, 1e-12);
+        assert_eq!(pearson(&x, &[3.0; 5]), None);
+        // R cor.test: p = 0.1240
+        assert_close(correlation_p_value(0.774_596_669_241_483_4, 5), 0.124_027_1, 1e-6);
+    }
+
+    #[test]
+    fn association_measures() {
+        let a = ["x", "x", "y", "y"];
+        assert_close(cramers_v(&a, &["u", "u", "v", "v"]).unwrap().0, 1.0, 1e-12);
+        assert_close(cramers_v(&a, &["u", "v", "u", "v"]).unwrap().0, 0.0, 1e-12);
+        assert_close(correlation_ratio(&a, &[1.0, 1.0, 3.0, 3.0]).unwrap(), 1.0, 1e-12);
+        assert_close(correlation_ratio(&a, &[1.0, 3.0, 1.0, 3.0]).unwrap(), 0.0, 1e-12);
+    }
+}
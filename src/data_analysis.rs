@@ -1,188 +1,463 @@
 // src/data_analysis.rs
 
 use polars::prelude::*;
-use serde::{Deserialize, Serialize};
-use polars::lazy::prelude::LazyCsvReader;
+use serde::{de, Deserialize, Deserializer, Serialize};
 use plotters::prelude::*;
 use serde_json::json;
-
-
-
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use rust_web_app::binning::{BinStrategy, Histogram};
+use crate::bootstrap::{bootstrap_difference, bootstrap_statistic, BootstrapConfig, Statistic};
+use crate::cache::{is_cache_file, read_cache};
+use crate::cleaning::{CleaningLog, CleaningPipeline};
+use crate::columns::{column, normalize_header, resolve, Col};
+use crate::compensation::{add_compensation_columns, Metric};
+use crate::error::{AppError, AppResult};
+use crate::gazetteer::add_location_columns;
+use crate::imputation::{impute, imputed_counts, print_imputation_summary, ImputationPolicy};
+use crate::outliers::{apply_rules, OutlierRule};
+use crate::storage::{is_database_file, SurveyDb};
+use crate::survey_years::{add_survey_year, YearSchema, CURRENT_YEAR, SURVEY_YEAR_COL};
+use crate::tech_taxonomy::{add_tech_columns, explode_other_techs, MAIN_TECH_CANONICAL_COL, OTHER_TECH_COL};
+use crate::validation::{check_record, RowError, RowErrorKind, ValidationReport};
+
+/// Ein Datensatz der Umfrage; die `rename`-Werte entsprechen `Col::header`,
+/// die `alias`-Werte `Col::key` (kurze Namen für JSON, z. B. in `POST /responses`)
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SurveyRecord {
-    #[serde(rename = "Timestamp")]
+    #[serde(rename = "Timestamp", alias = "timestamp")]
     pub timestamp: Option<String>,
 
-    #[serde(rename = "Age")]
-    pub age: Option<u32>,
+    #[serde(rename = "Age", alias = "age")]
+    pub age: Option<f64>,  // die bereinigte Datei enthält ergänzte Mittelwerte
 
-    #[serde(rename = "Gender")]
+    #[serde(rename = "Gender", alias = "gender")]
     pub gender: Option<String>,
 
-    #[serde(rename = "City")]
+    #[serde(rename = "City", alias = "city")]
     pub city: Option<String>,
 
-    #[serde(rename = "Position")]
+    #[serde(rename = "Position", alias = "position")]
     pub position: Option<String>,
 
-    #[serde(rename = "Total years of experience")]
+    #[serde(rename = "Total years of experience", alias = "experience")]
     pub total_experience: Option<f64>,  // f64 statt f32, wenn gewünscht
 
-    #[serde(rename = "Years of experience in Germany")]
+    #[serde(rename = "Years of experience in Germany", alias = "experience_germany")]
     pub experience_germany: Option<f64>,
 
-    #[serde(rename = "Seniority level")]
+    #[serde(rename = "Seniority level", alias = "seniority")]
     pub seniority_level: Option<String>,
 
-    #[serde(rename = "Your main technology / programming language")]
+    #[serde(rename = "Your main technology / programming language", alias = "main_tech")]
     pub main_tech: Option<String>,
 
-    #[serde(rename = "Other technologies/programming languages you use often")]
+    #[serde(rename = "Other technologies/programming languages you use often", alias = "other_techs")]
     pub other_techs: Option<String>,
 
-    #[serde(rename = "Yearly brutto salary (without bonus and stocks) in EUR")]
+    #[serde(rename = "Yearly brutto salary (without bonus and stocks) in EUR", alias = "salary")]
     pub yearly_brutto_salary: Option<f64>,  // auf f64 angepasst
 
-    #[serde(rename = "Yearly bonus + stocks in EUR")]
+    #[serde(rename = "Yearly bonus + stocks in EUR", alias = "bonus")]
     pub yearly_bonus_stocks: Option<f64>,
 
-    #[serde(rename = "Annual brutto salary (without bonus and stocks) one year ago. Only answer if staying in the same country")]
+    #[serde(rename = "Annual brutto salary (without bonus and stocks) one year ago. Only answer if staying in the same country", alias = "salary_last_year")]
     pub yearly_brutto_salary_last: Option<f64>,
 
-    #[serde(rename = "Annual bonus+stocks one year ago. Only answer if staying in same country")]
+    #[serde(rename = "Annual bonus+stocks one year ago. Only answer if staying in same country", alias = "bonus_last_year")]
     pub bonus_stocks_last: Option<f64>,
 
-    #[serde(rename = "Number of vacation days")]
+    #[serde(rename = "Number of vacation days", alias = "vacation_days", default, deserialize_with = "deserialize_whole_number")]
     pub vacation_days: Option<u32>,
 
-    #[serde(rename = "Employment status")]
+    #[serde(rename = "Employment status", alias = "employment_status")]
     pub employment_status: Option<String>,
 
-    #[serde(rename = "Сontract duration")]
+    #[serde(rename = "Сontract duration", alias = "contract_duration")]
     pub contract_duration: Option<String>,
 
-    #[serde(rename = "Main language at work")]
+    #[serde(rename = "Main language at work", alias = "work_language")]
     pub work_language: Option<String>,
 
-    #[serde(rename = "Company size")]
+    #[serde(rename = "Company size", alias = "company_size")]
     pub company_size: Option<String>,
 
-    #[serde(rename = "Company type")]
+    #[serde(rename = "Company type", alias = "company_type")]
     pub company_type: Option<String>,
 
-    #[serde(rename = "Have you lost your job due to the coronavirus outbreak?")]
+    #[serde(rename = "Have you lost your job due to the coronavirus outbreak?", alias = "lost_job_covid")]
     pub lost_job_covid: Option<String>,
 
-    #[serde(rename = "Have you been forced to have a shorter working week (Kurzarbeit)? If yes, how many hours per week")]
+    #[serde(rename = "Have you been forced to have a shorter working week (Kurzarbeit)? If yes, how many hours per week", alias = "shorter_work_week")]
     pub shorter_work_week: Option<String>,
 
-    #[serde(rename = "Have you received additional monetary support from your employer due to Work From Home? If yes, how much in 2020 in EUR")]
+    #[serde(rename = "Have you received additional monetary support from your employer due to Work From Home? If yes, how much in 2020 in EUR", alias = "wfh_support")]
     pub wfh_support: Option<f64>,
 }
 
-/// CSV mit Polars laden und in ein DataFrame konvertieren
-pub fn load_data(path: &str) -> PolarsResult<DataFrame> {
-    let lazy_frame = LazyCsvReader::new(path)
-        .with_infer_schema_length(Some(10_000))
-        .with_has_header(true)
-        // falls du "No"-Zellen ignorieren möchtest, kannst du:
-        // .with_null_values(Some(NullValues::AllColumns(vec!["No".to_string()])))
-        // oder
-        // .with_ignore_errors(true)
-        .finish()?;
-
-    let df = lazy_frame.collect()?;
-    Ok(df)
+impl SurveyRecord {
+    /// Textfeld zu einer Spalte (None für Zahlenfelder)
+    pub fn text_mut(&mut self, col: Col) -> Option<&mut Option<String>> {
+        match col {
+            Col::Timestamp => Some(&mut self.timestamp),
+            Col::Gender => Some(&mut self.gender),
+            Col::City => Some(&mut self.city),
+            Col::Position => Some(&mut self.position),
+            Col::Seniority => Some(&mut self.seniority_level),
+            Col::MainTech => Some(&mut self.main_tech),
+            Col::OtherTechs => Some(&mut self.other_techs),
+            Col::EmploymentStatus => Some(&mut self.employment_status),
+            Col::ContractDuration => Some(&mut self.contract_duration),
+            Col::WorkLanguage => Some(&mut self.work_language),
+            Col::CompanySize => Some(&mut self.company_size),
+            Col::CompanyType => Some(&mut self.company_type),
+            Col::LostJobCovid => Some(&mut self.lost_job_covid),
+            Col::ShorterWorkWeek => Some(&mut self.shorter_work_week),
+            _ => None,
+        }
+    }
 }
 
-/// Beispiel: Droppe Zeilen mit null in ALLEN Spalten
-pub fn clean_data(df: &mut DataFrame) -> PolarsResult<()> {
-    // Polars erfordert manchmal eine Typangabe:
-    *df = df.drop_nulls::<String>(None)?;
-    Ok(())
+/// Sammelgruppe für kleine oder fehlende Gruppen
+pub const OTHER_GROUP: &str = "other";
+
+/// Optionale Zahlenfelder: nicht lesbare Werte (z. B. "unlimited", "No", "0,00") werden beim
+/// Laden als leer gelesen und als Warnung im Bericht festgehalten, statt die Zeile zu verwerfen
+const OPTIONAL_NUMBER_COLS: [Col; 8] = [
+    Col::Age,
+    Col::Experience,
+    Col::ExperienceGermany,
+    Col::Bonus,
+    Col::SalaryLastYear,
+    Col::BonusLastYear,
+    Col::VacationDays,
+    Col::WfhSupport,
+];
+
+/// Ganzzahl, die auch als "26.0" geschrieben sein darf (so exportiert Polars sie)
+fn deserialize_whole_number<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<f64>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(v) if v.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&v) => Ok(Some(v as u32)),
+        Some(v) => Err(de::Error::custom(format!("{v} ist keine nicht-negative ganze Zahl"))),
+    }
 }
 
-/// Explorative Datenanalyse (Beispiel)
-pub fn eda(df: &DataFrame) {
-    println!("=== EDA ===");
-    println!("Shape: {:?}", df.shape());
-    println!("Schema: {:?}", df.schema());
+/// Zeile mit leeren statt nicht lesbaren Werten in `OPTIONAL_NUMBER_COLS`, dazu je Wert eine Warnung.
+/// `headers` sind die auf `Col::header` abgebildeten Spaltennamen der Datei.
+fn blank_unreadable_numbers(
+    row: &csv::StringRecord,
+    headers: &csv::StringRecord,
+    file_headers: &csv::StringRecord,
+    line: u64,
+) -> (csv::StringRecord, Vec<RowError>) {
+    let mut warnings = Vec::new();
+    let fields = row
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let Some(col) = OPTIONAL_NUMBER_COLS.into_iter().find(|c| headers.get(i) == Some(c.header())) else {
+                return value;
+            };
+            let readable = value.is_empty()
+                || value.parse::<f64>().is_ok_and(|v| {
+                    col != Col::VacationDays || (v.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&v))
+                });
+            if readable {
+                return value;
+            }
+            warnings.push(RowError {
+                line,
+                column: file_headers.get(i).map(str::to_string),
+                kind: RowErrorKind::InvalidValue,
+                message: format!("'{value}' ist keine Zahl und wird als leer gelesen"),
+            });
+            ""
+        })
+        .collect::<csv::StringRecord>();
+    (fields, warnings)
+}
 
-    // Gehaltsspalte als f64 verarbeiten
-    if let Ok(salary_series) = df.column("Yearly brutto salary (without bonus and stocks) in EUR") {
-        if let Ok(salary_f64) = salary_series.f64() {
-            let mean_salary = salary_f64.mean().unwrap_or(f64::NAN);
-            let std_salary = salary_f64.std(1).unwrap_or(f64::NAN);
-            println!("Durchschnittliches Gehalt: {:.2}", mean_salary);
-            println!("Standardabweichung (ddof=1): {:.2}", std_salary);
-        } else {
-            println!("'Yearly brutto salary...' ist nicht vom Typ f64.");
+/// Werte der Spalten aus `YearSchema::added` zu ihrer Zielspalte addieren; `added` enthält je
+/// Spalte den Index in der Datei und den der Zielspalte. Nicht lesbare Werte zählen als leer.
+fn add_extra_numbers(
+    row: csv::StringRecord,
+    added: &[(usize, usize)],
+    file_headers: &csv::StringRecord,
+    line: u64,
+) -> (csv::StringRecord, Vec<RowError>) {
+    let mut fields = row.iter().map(str::to_string).collect::<Vec<String>>();
+    let mut warnings = Vec::new();
+    for &(source, target) in added {
+        let value = row.get(source).unwrap_or_default();
+        if value.is_empty() {
+            continue;
+        }
+        let Ok(extra) = value.parse::<f64>() else {
+            warnings.push(RowError {
+                line,
+                column: file_headers.get(source).map(str::to_string),
+                kind: RowErrorKind::InvalidValue,
+                message: format!("'{value}' ist keine Zahl und wird als leer gelesen"),
+            });
+            continue;
+        };
+        if let Some(field) = fields.get_mut(target) {
+            // Zielwert ist nach `blank_unreadable_numbers` leer oder eine Zahl
+            *field = (field.parse::<f64>().unwrap_or(0.0) + extra).to_string();
         }
     }
+    (fields.into_iter().collect(), warnings)
 }
 
-/// Einfache lineare Regression (Gehalt ~ Erfahrung) mit f64
-pub fn simple_regression_example(df: &DataFrame) {
-    let experience = df
-        .column("Total years of experience")
-        .unwrap()
-        .f64()
-        .unwrap()
-        .into_no_null_iter()
-        .collect::<Vec<f64>>();
-
-    let salary = df
-        .column("Yearly brutto salary (without bonus and stocks) in EUR")
-        .unwrap()
-        .f64()
-        .unwrap()
-        .into_no_null_iter()
-        .collect::<Vec<f64>>();
-
-    if experience.is_empty() || salary.is_empty() || experience.len() != salary.len() {
-        println!("Nicht genügend Daten für Regression!");
-        return;
+/// CSV einer Erhebung zeilenweise in `SurveyRecord` einlesen. Verworfen werden Zeilen ohne
+/// Gehalt oder mit unmöglichen Werten; nicht lesbare optionale Zahlen bleiben leer.
+/// Eine `.arrow`-Datei (siehe `cache`) ist bereits aufbereitet und wird unverändert geladen,
+/// aus einer SQLite-Datenbank (siehe `storage`) kommen die gespeicherten Antworten des Jahres.
+pub fn load_data(path: &str, year: i32) -> PolarsResult<(DataFrame, ValidationReport)> {
+    if is_database_file(path) {
+        return SurveyDb::open(path)?.read_year(year);
     }
-
-    let n = experience.len() as f64;
-    let mean_x = experience.iter().sum::<f64>() / n;
-    let mean_y = salary.iter().sum::<f64>() / n;
-
-    let mut cov_xy = 0.0;
-    let mut var_x = 0.0;
-    for (&x, &y) in experience.iter().zip(salary.iter()) {
-        let dx = x - mean_x;
-        let dy = y - mean_y;
-        cov_xy += dx * dy;
-        var_x += dx * dx;
+    if is_cache_file(path) {
+        let (mut df, report) = read_cache(Path::new(path))?;
+        if df.column(SURVEY_YEAR_COL).is_err() {
+            add_survey_year(&mut df, year)?;
+        }
+        return Ok((df, report));
     }
 
-    let slope = cov_xy / var_x;
-    let intercept = mean_y - slope * mean_x;
+    let schema = YearSchema::for_year(year)
+        .ok_or_else(|| polars_err!(InvalidOperation: "Kein Schema für das Jahr {} bekannt", year))?;
+    let file = File::open(path)?;
+    load_with_schema(file, path, &schema)
+}
+
+/// Ergebnis einer SQL-Abfrage auf die Antwort-Datenbank als DataFrame
+pub fn load_data_from_query(db_path: &str, sql: &str) -> PolarsResult<DataFrame> {
+    SurveyDb::open(db_path)?.query(sql)
+}
+
+/// Wie `load_data`, aber aus einer beliebigen Quelle (z. B. einem Upload)
+pub fn load_data_from_reader<R: Read>(reader: R, source: &str) -> PolarsResult<(DataFrame, ValidationReport)> {
+    let schema = YearSchema::for_year(CURRENT_YEAR).expect("Schema für das aktuelle Jahr fehlt");
+    load_with_schema(reader, source, &schema)
+}
+
+/// Gemeinsamer Lader: Kopfzeile über das Jahresschema zuordnen, Zeilen prüfen,
+/// gültige Zeilen in das gemeinsame Schema samt `survey_year` überführen
+fn load_with_schema<R: Read>(reader: R, source: &str, schema: &YearSchema) -> PolarsResult<(DataFrame, ValidationReport)> {
+    let registry = schema.registry();
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let file_headers = csv_reader
+        .headers()
+        .map_err(|e| polars_err!(ComputeError: "Kopfzeile von {} nicht lesbar: {}", source, e))?
+        .clone();
+
+    // Spaltennamen der Datei auf die Namen aus `SurveyRecord` abbilden,
+    // damit Leerzeichen, Groß-/Kleinschreibung, Homoglyphen und die
+    // Bezeichnungen älterer Jahrgänge keine Rolle spielen
+    let headers = file_headers
+        .iter()
+        .map(|h| -> &str { registry.identify(h).map_or(h, |col| col.header()) })
+        .collect::<csv::StringRecord>();
+
+    let find_in_file = |header: &str| file_headers.iter().position(|h| normalize_header(h) == normalize_header(header));
+    let mut missing = schema
+        .required()
+        .filter(|col| !headers.iter().any(|h| h == col.header()))
+        .map(|col| col.header())
+        .collect::<Vec<&str>>();
+    missing.extend(schema.added.iter().map(|(_, header)| *header).filter(|h| find_in_file(h).is_none()));
+    if !missing.is_empty() {
+        polars_bail!(ColumnNotFound: "{} fehlen die Spalten {:?}", source, missing);
+    }
+    let added = schema
+        .added
+        .iter()
+        .filter_map(|(col, header)| Some((find_in_file(header)?, headers.iter().position(|h| h == col.header())?)))
+        .collect::<Vec<(usize, usize)>>();
+
+    let mut report = ValidationReport::new(source);
+    let mut records = Vec::new();
+    for result in csv_reader.records() {
+        let row = match result {
+            Ok(row) => row,
+            Err(e) => {
+                let line = e.position().map(|p| p.line()).unwrap_or(0);
+                report.record_dropped(vec![RowError {
+                    line,
+                    column: None,
+                    kind: RowErrorKind::MalformedRow,
+                    message: e.to_string(),
+                }]);
+                continue;
+            }
+        };
+        let line = row.position().map(|p| p.line()).unwrap_or(0);
+        let (row, mut warnings) = blank_unreadable_numbers(&row, &headers, &file_headers, line);
+        let (row, extra_warnings) = add_extra_numbers(row, &added, &file_headers, line);
+        warnings.extend(extra_warnings);
+
+        match row.deserialize::<SurveyRecord>(Some(&headers)) {
+            Ok(record) => {
+                let mut errors = check_record(&record, line);
+                // Ohne Gehalt ist eine Zeile für keine Auswertung brauchbar
+                if record.yearly_brutto_salary.is_none() {
+                    errors.push(RowError {
+                        line,
+                        column: Some(Col::Salary.header().to_string()),
+                        kind: RowErrorKind::MissingValue,
+                        message: "Gehalt fehlt".to_string(),
+                    });
+                }
+                if errors.is_empty() {
+                    report.record_valid();
+                    report.record_warnings(warnings);
+                    records.push(record);
+                } else {
+                    report.record_dropped(errors);
+                }
+            }
+            Err(e) => {
+                let (column, message) = match e.kind() {
+                    csv::ErrorKind::Deserialize { err, .. } => {
+                        let field = err.field().map(|i| i as usize);
+                        let value = field.and_then(|i| row.get(i)).unwrap_or_default();
+                        (
+                            field.and_then(|i| file_headers.get(i)).map(str::to_string),
+                            format!("'{value}': {}", err.kind()),
+                        )
+                    }
+                    _ => (None, e.to_string()),
+                };
+                report.record_dropped(vec![RowError {
+                    line,
+                    column,
+                    kind: RowErrorKind::InvalidValue,
+                    message,
+                }]);
+            }
+        }
+    }
 
-    println!("Lineare Regression (Gehalt ~ Erfahrung): slope={:.3}, intercept={:.3}", slope, intercept);
+    let mut df = records_to_dataframe(&records)?;
+    add_survey_year(&mut df, schema.year)?;
+    Ok((df, report))
+}
 
-    let predicted_salary = intercept + slope * 5.0;
-    println!("Geschätztes Gehalt für 5 Jahre Erfahrung = {:.2} EUR", predicted_salary);
+/// Gültige Datensätze in ein DataFrame mit den Original-Spaltennamen überführen
+pub fn records_to_dataframe(records: &[SurveyRecord]) -> PolarsResult<DataFrame> {
+    let text = |col: Col, get: fn(&SurveyRecord) -> Option<&str>| {
+        Column::new(col.header().into(), records.iter().map(get).collect::<Vec<_>>())
+    };
+    let whole = |col: Col, get: fn(&SurveyRecord) -> Option<u32>| {
+        Column::new(col.header().into(), records.iter().map(get).collect::<Vec<_>>())
+    };
+    let number = |col: Col, get: fn(&SurveyRecord) -> Option<f64>| {
+        Column::new(col.header().into(), records.iter().map(get).collect::<Vec<_>>())
+    };
+
+    DataFrame::new(vec![
+        text(Col::Timestamp, |r| r.timestamp.as_deref()),
+        number(Col::Age, |r| r.age),
+        text(Col::Gender, |r| r.gender.as_deref()),
+        text(Col::City, |r| r.city.as_deref()),
+        text(Col::Position, |r| r.position.as_deref()),
+        number(Col::Experience, |r| r.total_experience),
+        number(Col::ExperienceGermany, |r| r.experience_germany),
+        text(Col::Seniority, |r| r.seniority_level.as_deref()),
+        text(Col::MainTech, |r| r.main_tech.as_deref()),
+        text(Col::OtherTechs, |r| r.other_techs.as_deref()),
+        number(Col::Salary, |r| r.yearly_brutto_salary),
+        number(Col::Bonus, |r| r.yearly_bonus_stocks),
+        number(Col::SalaryLastYear, |r| r.yearly_brutto_salary_last),
+        number(Col::BonusLastYear, |r| r.bonus_stocks_last),
+        whole(Col::VacationDays, |r| r.vacation_days),
+        text(Col::EmploymentStatus, |r| r.employment_status.as_deref()),
+        text(Col::ContractDuration, |r| r.contract_duration.as_deref()),
+        text(Col::WorkLanguage, |r| r.work_language.as_deref()),
+        text(Col::CompanySize, |r| r.company_size.as_deref()),
+        text(Col::CompanyType, |r| r.company_type.as_deref()),
+        text(Col::LostJobCovid, |r| r.lost_job_covid.as_deref()),
+        text(Col::ShorterWorkWeek, |r| r.shorter_work_week.as_deref()),
+        number(Col::WfhSupport, |r| r.wfh_support),
+    ])
 }
 
+/// Standard-Bereinigung (siehe `CleaningPipeline::survey_2020`) anwenden
+/// und abgeleitete Spalten (kanonische Technologien, Ort/Bundesland) ergänzen
+pub fn clean_data(df: &mut DataFrame) -> PolarsResult<CleaningLog> {
+    let log = CleaningPipeline::survey_2020().apply(df)?;
+    add_tech_columns(df)?;
+    add_location_columns(df)?;
+    Ok(log)
+}
 
+/// Bereinigung, Imputation, Vergütungsspalten und Ausreißer-Regeln nacheinander anwenden.
+/// Liefert die angewendeten Schritte in lesbarer Form.
+pub fn prepare_survey(df: &mut DataFrame, imputation: &[ImputationPolicy], outliers: &[OutlierRule]) -> PolarsResult<Vec<String>> {
+    let cleaning_log = clean_data(df)?;
+    cleaning_log.print_summary();
+    println!("Nach clean_data: Shape: {:?}", df.shape());
+    let mut steps: Vec<String> = cleaning_log.steps.iter().map(|log| log.step.clone()).collect();
+    steps.push("add_tech_columns".to_string());
+    steps.push("add_location_columns".to_string());
+
+    // Vor der Imputation: ein fehlender Bonus zählt für die Gesamtvergütung als 0
+    add_compensation_columns(df)?;
+    steps.push("add_compensation_columns".to_string());
+
+    let imputation_log = impute(df, imputation)?;
+    print_imputation_summary(&imputation_log);
+    steps.extend(imputation_log.iter().map(|log| format!("impute {}: {}", log.column, log.strategy)));
+
+    for summary in apply_rules(df, outliers)? {
+        summary.print_summary();
+        steps.push(format!("outliers {}: {} ({:?})", summary.column, summary.method, summary.action));
+    }
+    Ok(steps)
+}
 
+/// Explorative Datenanalyse (Beispiel)
+pub fn eda(df: &DataFrame) {
+    println!("=== EDA ===");
+    println!("Shape: {:?}", df.shape());
+    println!("Schema: {:?}", df.schema());
 
-pub fn create_salary_histogram_and_save(df: &DataFrame, file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let col_name = "Yearly brutto salary (without bonus and stocks) in EUR";
+    // Gehaltsspalte als f64 verarbeiten
+    match column(df, Col::Salary) {
+        Ok(salary_series) => {
+            if let Ok(salary_f64) = salary_series.f64() {
+                let mean_salary = salary_f64.mean().unwrap_or(f64::NAN);
+                let std_salary = salary_f64.std(1).unwrap_or(f64::NAN);
+                println!("Durchschnittliches Gehalt: {:.2}", mean_salary);
+                println!("Standardabweichung (ddof=1): {:.2}", std_salary);
+            } else {
+                println!("'{}' ist nicht vom Typ f64.", Col::Salary.key());
+            }
+        }
+        Err(e) => println!("{e}"),
+    }
 
-    // Extract salary column as Vec<f32>
-    let salaries = df
-        .column(col_name)
-        .expect("Spalte nicht gefunden")
-        .f64()
-        .expect("Spalte ist nicht vom Typ f64")
-        .into_no_null_iter()
-        .map(|v| v as f32)
-        .collect::<Vec<f32>>();
+    for (name, count) in imputed_counts(df) {
+        println!("Ergänzte Werte in '{name}': {count}");
+    }
+}
 
-    println!("DEBUG: Salaries passed to the histogram: {:?}", salaries);
+pub fn create_salary_histogram_and_save(
+    df: &DataFrame,
+    file_path: &str,
+    strategy: &BinStrategy,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let salaries = numeric_values(df, Col::Salary)?;
+    let histogram = strategy.histogram(&salaries)?;
 
     // Generate the histogram
     let width = 800;
@@ -191,27 +466,9 @@ pub fn create_salary_histogram_and_save(df: &DataFrame, file_path: &str) -> Resu
         .into_drawing_area();
     root.fill(&WHITE)?;
 
-    let min_sal = salaries.iter().cloned().reduce(f32::min).unwrap_or(0.0);
-    let max_sal = salaries.iter().cloned().reduce(f32::max).unwrap_or(1.0);
-    let bin_count = 10;
-    let bin_size = (max_sal - min_sal) / bin_count as f32;
-    let mut freq = vec![0; bin_count];
-
-    for &val in salaries.iter() {
-        let mut idx = ((val - min_sal) / bin_size).floor() as isize;
-        if idx < 0 {
-            idx = 0;
-        }
-        if idx as usize >= bin_count {
-            idx = bin_count as isize - 1;
-        }
-        freq[idx as usize] += 1;
-    }
-
-    println!("DEBUG: Bin count = {}, Bin size = {}", bin_count, bin_size);
-    println!("DEBUG: Frequencies = {:?}", freq);
-
-    let max_freq = *freq.iter().max().unwrap_or(&1);
+    let min_sal = histogram.edges.first().copied().unwrap_or(0.0);
+    let max_sal = histogram.edges.last().copied().unwrap_or(1.0);
+    let max_freq = histogram.counts.iter().copied().max().unwrap_or(1);
 
     let mut chart = ChartBuilder::on(&root)
         .caption("Salary Histogram", ("sans-serif", 20).into_font())
@@ -225,12 +482,10 @@ pub fn create_salary_histogram_and_save(df: &DataFrame, file_path: &str) -> Resu
         .y_desc("Frequency")
         .draw()?;
 
-    for (i, &count) in freq.iter().enumerate() {
-        let x0 = min_sal + i as f32 * bin_size;
-        let x1 = x0 + bin_size;
+    for (edges, &count) in histogram.edges.windows(2).zip(&histogram.counts) {
         chart
             .draw_series(std::iter::once(Rectangle::new(
-                [(x0, 0), (x1, count)],
+                [(edges[0], 0), (edges[1], count)],
                 BLUE.filled(),
             )))?;
     }
@@ -241,101 +496,426 @@ pub fn create_salary_histogram_and_save(df: &DataFrame, file_path: &str) -> Resu
     Ok(())
 }
 
-pub fn calculate_summary_statistics(df: &DataFrame) -> serde_json::Value {
-    let salary_col = "Yearly brutto salary (without bonus and stocks) in EUR";
-    let experience_col = "Total years of experience";
-
-    let salary = df
-        .column(salary_col)
-        .expect("Spalte nicht gefunden")
-        .f64()
-        .expect("Spalte ist nicht vom Typ f64")
-        .into_no_null_iter()
-        .collect::<Vec<f64>>();
+/// EDA-Kennzahlen der gewählten Vergütungsbestandteile (Schlüssel siehe `Metric::key`) und der Erfahrung
+pub fn calculate_summary_statistics(
+    df: &DataFrame,
+    metrics: &[Metric],
+    bootstrap: Option<&BootstrapConfig>,
+) -> AppResult<serde_json::Value> {
+    ensure_rows(df)?;
+    let experience = numeric_values(df, Col::Experience)?;
+
+    let mut summary = json!({
+        "metrics": metrics.iter().map(Metric::label).collect::<Vec<_>>(),
+        "experience": describe(&experience, bootstrap),
+        "imputed": imputed_counts(df).into_iter().collect::<BTreeMap<String, usize>>(),
+        "bootstrap": bootstrap.map(BootstrapConfig::describe),
+    });
+    for metric in metrics {
+        let values = numeric_series(df, &metric.column(df)?)?.into_iter().flatten().collect::<Vec<f64>>();
+        summary[metric.key()] = describe(&values, bootstrap);
+    }
+    Ok(summary)
+}
 
-    let experience = df
-        .column(experience_col)
-        .expect("Spalte nicht gefunden")
-        .f64()
-        .expect("Spalte ist nicht vom Typ f64")
-        .into_no_null_iter()
-        .collect::<Vec<f64>>();
+/// Kennzahlen wie in `/eda-summary` für eine Werteliste, mit Bootstrap-Intervallen für Mittelwert und Median
+fn describe(values: &[f64], bootstrap: Option<&BootstrapConfig>) -> serde_json::Value {
+    let mut summary = json!({
+        "mean": mean(values),
+        "median": median(values),
+        "std_dev": std_dev(values),
+        "min": values.iter().cloned().fold(f64::INFINITY, f64::min),
+        "max": values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+    });
+    if let Some(config) = bootstrap {
+        summary["ci"] = json!({
+            "mean": bootstrap_statistic(values, Statistic::Mean, config).map(|i| i.describe()),
+            "median": bootstrap_statistic(values, Statistic::Median, config).map(|i| i.describe()),
+        });
+    }
+    summary
+}
 
+/// Differenz von Mittelwert und Median zur Referenzgruppe mit Bootstrap-Intervallen
+fn describe_difference(values: &[f64], reference: &[f64], config: &BootstrapConfig) -> serde_json::Value {
     json!({
-        "salary": {
-            "mean": mean(&salary),
-            "median": median(&salary),
-            "std_dev": std_dev(&salary),
-            "min": salary.iter().cloned().fold(f64::INFINITY, f64::min),
-            "max": salary.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
-        },
-        "experience": {
-            "mean": mean(&experience),
-            "median": median(&experience),
-            "std_dev": std_dev(&experience),
-            "min": experience.iter().cloned().fold(f64::INFINITY, f64::min),
-            "max": experience.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
-        },
+        "mean": bootstrap_difference(values, reference, Statistic::Mean, config).map(|i| i.describe()),
+        "median": bootstrap_difference(values, reference, Statistic::Median, config).map(|i| i.describe()),
     })
 }
 
-pub fn calculate_distribution(df: &DataFrame) -> serde_json::Value {
-    let salary_col = "Yearly brutto salary (without bonus and stocks) in EUR";
+/// EDA-Kennzahlen je Wert der Spalte `group_col` (z. B. Ort oder Bundesland).
+/// Gruppen mit weniger als `min_count` Befragten und fehlende Werte landen in "other".
+/// Mit Bootstrap erhält jede Gruppe außerdem die Differenz der Kennzahl zur größten Gruppe
+/// (`<key>_difference`, z. B. `salary_difference`).
+pub fn calculate_grouped_summary(
+    df: &DataFrame,
+    group_col: &str,
+    metric: Metric,
+    min_count: usize,
+    bootstrap: Option<&BootstrapConfig>,
+) -> AppResult<serde_json::Value> {
+    let groups = text_series(df, group_col)?;
+    let salary = numeric_series(df, &metric.column(df)?)?;
+    let experience = numeric_column(df, Col::Experience)?;
+
+    let mut counts = HashMap::<&str, usize>::new();
+    for group in groups.into_iter().flatten() {
+        *counts.entry(group).or_insert(0) += 1;
+    }
 
-    let salaries = df
-        .column(salary_col)
-        .expect("Spalte nicht gefunden")
-        .f64()
-        .expect("Spalte ist nicht vom Typ f64")
-        .into_no_null_iter()
-        .collect::<Vec<f64>>();
+    let mut values = BTreeMap::<&str, (usize, Vec<f64>, Vec<f64>)>::new();
+    for ((group, sal), exp) in groups.into_iter().zip(&salary).zip(&experience) {
+        let label = group
+            .filter(|g| counts[g] >= min_count)
+            .unwrap_or(OTHER_GROUP);
+        let entry = values.entry(label).or_default();
+        entry.0 += 1;
+        entry.1.extend(sal);
+        entry.2.extend(exp);
+    }
 
-    let min_sal = salaries.iter().cloned().fold(f64::INFINITY, f64::min);
-    let max_sal = salaries.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-    let bin_count = 10;
-    let bin_size = (max_sal - min_sal) / bin_count as f64;
-    let mut freq = vec![0; bin_count];
+    let mut values = values.into_iter().collect::<Vec<_>>();
+    values.sort_by_key(|(_, (count, _, _))| std::cmp::Reverse(*count));
+    let reference = values.first().map(|(label, (_, sal, _))| (*label, sal.clone()));
+
+    let result = values
+        .iter()
+        .map(|(label, (count, sal, exp))| {
+            let mut group = json!({
+                "group": label,
+                "count": count,
+                "experience": describe(exp, bootstrap),
+            });
+            group[metric.key()] = describe(sal, bootstrap);
+            if let (Some(config), Some((reference_label, reference))) = (bootstrap, &reference) {
+                if label != reference_label {
+                    group[format!("{}_difference", metric.key())] = describe_difference(sal, reference, config);
+                }
+            }
+            group
+        })
+        .collect::<Vec<_>>();
+
+    Ok(json!({
+        "group_by": group_col,
+        "metric": metric.label(),
+        "min_count": min_count,
+        "reference": bootstrap.and(reference.as_ref().map(|(label, _)| *label)),
+        "bootstrap": bootstrap.map(BootstrapConfig::describe),
+        "groups": result,
+    }))
+}
 
-    for &val in salaries.iter() {
-        let mut idx = ((val - min_sal) / bin_size).floor() as isize;
-        if idx < 0 {
-            idx = 0;
-        }
-        if idx as usize >= bin_count {
-            idx = bin_count as isize - 1;
+/// Anzahl und Median der Kennzahl (`median_<key>`, z. B. `median_salary`) je kanonischer
+/// Technologie (Haupt- und weitere Technologien)
+pub fn calculate_tech_summary(df: &DataFrame, metric: Metric) -> AppResult<serde_json::Value> {
+    let metric_col = metric.column(df)?;
+    let median_key = format!("median_{}", metric.key());
+
+    let summarize = |frame: DataFrame, tech_col: &str| -> AppResult<Vec<serde_json::Value>> {
+        let grouped = frame
+            .lazy()
+            .filter(col(tech_col).is_not_null())
+            .group_by([col(tech_col).alias("technology")])
+            .agg([
+                len().alias("count"),
+                col(metric_col.as_str()).median().alias(median_key.as_str()),
+            ])
+            .sort(["count"], SortMultipleOptions::default().with_order_descending(true))
+            .collect()?;
+
+        let technology = grouped.column("technology")?.str()?;
+        let count = grouped.column("count")?.idx()?;
+        let medians = grouped.column(&median_key)?.f64()?;
+        Ok(technology
+            .into_iter()
+            .zip(count)
+            .zip(medians)
+            .map(|((tech, count), median)| {
+                let mut entry = json!({ "technology": tech, "count": count });
+                entry[median_key.as_str()] = json!(median);
+                entry
+            })
+            .collect())
+    };
+
+    let long = explode_other_techs(df)?;
+    Ok(json!({
+        "metric": metric.label(),
+        "main_tech": summarize(df.clone(), MAIN_TECH_CANONICAL_COL)?,
+        "other_techs": summarize(long, OTHER_TECH_COL)?,
+    }))
+}
+
+/// Verteilung der Kennzahl (Standard: Grundgehalt) mit den Klassen aus `strategy`
+pub fn calculate_distribution(df: &DataFrame, metric: Metric, strategy: &BinStrategy) -> AppResult<serde_json::Value> {
+    ensure_rows(df)?;
+    let salaries = numeric_series(df, &metric.column(df)?)?.into_iter().flatten().collect::<Vec<f64>>();
+    let histogram = strategy.histogram(&salaries).map_err(AppError::InvalidParameter)?;
+    Ok(json!(histogram))
+}
+
+/// Standardanzahl der Klassen für `/distribution/by`
+pub const DEFAULT_GROUP_DISTRIBUTION_BINS: usize = 20;
+
+/// Verteilung einer numerischen Spalte je Wert von `group_col` auf gemeinsamen Klassengrenzen,
+/// damit die Gruppen vergleichbar sind. Die Gruppen ergeben sich aus den Daten; Gruppen mit
+/// weniger als `min_count` Werten und fehlende Werte landen in "other". Neben den Anzahlen
+/// wird die Dichte geliefert (Fläche je Gruppe = 1).
+pub fn calculate_group_distribution(
+    df: &DataFrame,
+    group_col: &str,
+    metric_col: &str,
+    strategy: &BinStrategy,
+    min_count: usize,
+) -> AppResult<serde_json::Value> {
+    let by_group = grouped_values(df, group_col, metric_col, min_count)?;
+
+    // Gemeinsame Klassengrenzen über alle Gruppen
+    let all = by_group.iter().flat_map(|(_, values)| values).copied().collect::<Vec<f64>>();
+    if all.is_empty() {
+        return Err(AppError::EmptyDataset(format!("keine Werte in '{metric_col}'")));
+    }
+    let edges = strategy.edges(&all).map_err(AppError::InvalidParameter)?;
+
+    let groups = by_group
+        .into_iter()
+        .map(|(name, values)| (name, values.len(), Histogram::count(strategy.label(), edges.clone(), &values)))
+        .collect::<Vec<_>>();
+
+    Ok(json!({
+        "group_by": group_col,
+        "metric": metric_col,
+        "strategy": strategy.label(),
+        "centers": edges.windows(2).map(|w| (w[0] + w[1]) / 2.0).collect::<Vec<f64>>(),
+        "edges": edges,
+        "groups": groups
+            .into_iter()
+            .map(|(name, count, histogram)| json!({
+                "name": name,
+                "count": count,
+                "counts": histogram.counts,
+                "density": histogram.density,
+                "outside": histogram.outside,
+            }))
+            .collect::<Vec<_>>(),
+    }))
+}
+
+/// Vorhandene Werte von `metric_col` je Wert von `group_col`, größte Gruppe zuerst. Gruppen mit
+/// weniger als `min_count` Werten und fehlende Gruppenwerte landen in "other" (am Ende).
+pub fn grouped_values(
+    df: &DataFrame,
+    group_col: &str,
+    metric_col: &str,
+    min_count: usize,
+) -> AppResult<Vec<(String, Vec<f64>)>> {
+    ensure_rows(df)?;
+    let group_column = df.column(group_col)?;
+    if group_column.dtype().is_nested() {
+        return Err(AppError::wrong_type(group_col, "einzelne Werte", group_column.dtype()));
+    }
+    let groups = group_column.cast(&DataType::String)?;
+    let groups = groups.str()?;
+    let values = numeric_series(df, metric_col)?;
+
+    let mut counts = HashMap::<&str, usize>::new();
+    for (group, value) in groups.into_iter().zip(&values) {
+        if let (Some(group), Some(_)) = (group, value) {
+            *counts.entry(group).or_insert(0) += 1;
         }
-        freq[idx as usize] += 1;
+    }
+    let mut by_group = HashMap::<&str, Vec<f64>>::new();
+    for (group, value) in groups.into_iter().zip(&values) {
+        let Some(value) = value else { continue };
+        let label = group.filter(|g| counts[g] >= min_count).unwrap_or(OTHER_GROUP);
+        by_group.entry(label).or_default().push(value);
     }
 
-    let bins = (0..bin_count)
-        .map(|i| min_sal + i as f64 * bin_size)
-        .collect::<Vec<f64>>();
+    let mut groups = by_group
+        .into_iter()
+        .map(|(name, values)| (name.to_string(), values))
+        .collect::<Vec<_>>();
+    groups.sort_by(|a, b| {
+        (a.0 == OTHER_GROUP)
+            .cmp(&(b.0 == OTHER_GROUP))
+            .then(b.1.len().cmp(&a.1.len()))
+            .then(a.0.cmp(&b.0))
+    });
+    Ok(groups)
+}
 
-    json!({
-        "bins": bins,
-        "frequencies": freq,
-    })
+/// Fehler, wenn das DataFrame (z. B. nach einem Filter) keine Zeilen enthält
+pub fn ensure_rows(df: &DataFrame) -> AppResult<()> {
+    if df.height() == 0 {
+        return Err(AppError::EmptyDataset("keine Zeilen für die gewählten Filter".to_string()));
+    }
+    Ok(())
+}
+
+/// Numerische Spalte als f64; ganzzahlige Spalten werden umgewandelt
+pub fn numeric_series(df: &DataFrame, name: &str) -> AppResult<Float64Chunked> {
+    let column = df.column(name)?;
+    if !column.dtype().is_numeric() {
+        return Err(AppError::wrong_type(name, "numerisch", column.dtype()));
+    }
+    Ok(column.cast(&DataType::Float64)?.f64()?.clone())
+}
+
+/// Textspalte
+pub fn text_series<'a>(df: &'a DataFrame, name: &str) -> AppResult<&'a StringChunked> {
+    let column = df.column(name)?;
+    column.str().map_err(|_| AppError::wrong_type(name, "Text", column.dtype()))
+}
+
+/// Wie `numeric_series`, Spalte über die Registry aufgelöst
+pub fn numeric_column(df: &DataFrame, col: Col) -> AppResult<Float64Chunked> {
+    numeric_series(df, &resolve(df, col)?)
+}
+
+/// Vorhandene Werte einer numerischen Spalte
+pub fn numeric_values(df: &DataFrame, col: Col) -> AppResult<Vec<f64>> {
+    Ok(numeric_column(df, col)?.into_iter().flatten().collect())
 }
 
 /// Hilfsfunktion: Mittelwert berechnen
-fn mean(data: &Vec<f64>) -> f64 {
+pub fn mean(data: &[f64]) -> f64 {
     data.iter().sum::<f64>() / data.len() as f64
 }
 
 /// Hilfsfunktion: Median berechnen
-fn median(data: &Vec<f64>) -> f64 {
-    let mut sorted = data.clone();
-    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+pub fn median(data: &[f64]) -> f64 {
+    if data.is_empty() {
+        return f64::NAN;
+    }
+    let mut sorted = data.to_vec();
+    sorted.sort_by(f64::total_cmp);
     let mid = sorted.len() / 2;
-    if sorted.len() % 2 == 0 {
+    if sorted.len().is_multiple_of(2) {
         (sorted[mid - 1] + sorted[mid]) / 2.0
     } else {
         sorted[mid]
     }
 }
 
+/// Hilfsfunktion: Quantil mit linearer Interpolation (`q` zwischen 0 und 1)
+pub fn quantile(data: &[f64], q: f64) -> f64 {
+    if data.is_empty() {
+        return f64::NAN;
+    }
+    let mut sorted = data.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let pos = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower = pos.floor() as usize;
+    let upper = pos.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (pos - lower as f64)
+}
+
 /// Hilfsfunktion: Standardabweichung berechnen
-fn std_dev(data: &Vec<f64>) -> f64 {
+fn std_dev(data: &[f64]) -> f64 {
     let mean_val = mean(data);
     (data.iter().map(|&x| (x - mean_val).powi(2)).sum::<f64>() / data.len() as f64).sqrt()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compensation::{BONUS_SHARE_COL, TOTAL_COMPENSATION_COL};
+    use crate::imputation::ImputationConfig;
+
+    fn record(value: serde_json::Value) -> SurveyRecord {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn unreadable_optional_numbers_keep_the_row() {
+        let header = Col::ALL.map(|col| format!("\"{}\"", col.header())).join(",");
+        let row = |values: &[(Col, &str)]| {
+            Col::ALL
+                .map(|col| values.iter().find(|(c, _)| *c == col).map_or("", |(_, v)| v))
+                .join(",")
+        };
+        let csv = [
+            header,
+            row(&[(Col::Age, "32.5"), (Col::Salary, "60000"), (Col::VacationDays, "unlimited"), (Col::WfhSupport, "No")]),
+            row(&[(Col::Age, "40"), (Col::Bonus, "depends")]),
+        ]
+        .join("\n");
+
+        let (df, report) = load_data_from_reader(csv.as_bytes(), "test.csv").unwrap();
+        assert_eq!(df.height(), 1);
+        assert_eq!(column(&df, Col::Age).unwrap().f64().unwrap().get(0), Some(32.5));
+        assert_eq!(column(&df, Col::VacationDays).unwrap().null_count(), 1);
+        assert_eq!((report.valid_rows, report.dropped_rows), (1, 1));
+        assert_eq!(report.warnings.len(), 2);
+        assert_eq!(report.errors_by_kind.get(&RowErrorKind::MissingValue), Some(&1));
+    }
+
+    #[test]
+    fn imputed_bonus_stays_out_of_total_compensation() {
+        let records = [
+            record(json!({ "age": 30, "experience": 5.0, "seniority": "Senior", "salary": 60_000.0, "bonus": 10_000.0 })),
+            record(json!({ "age": 35, "experience": 8.0, "seniority": "Senior", "salary": 70_000.0, "bonus": 20_000.0 })),
+            record(json!({ "age": 40, "experience": 9.0, "seniority": "Senior", "salary": 80_000.0 })),
+        ];
+        let mut df = records_to_dataframe(&records).unwrap();
+        prepare_survey(&mut df, &ImputationConfig::default().policies, &[]).unwrap();
+
+        // Der Bonus wird ergänzt, die Gesamtvergütung bleibt das Grundgehalt
+        let imputed = df.column("bonus_imputed").unwrap().bool().unwrap().get(2);
+        assert_eq!(imputed, Some(true));
+        let total = df.column(TOTAL_COMPENSATION_COL).unwrap().f64().unwrap().to_vec();
+        assert_eq!(total, vec![Some(70_000.0), Some(90_000.0), Some(80_000.0)]);
+        assert_eq!(df.column(BONUS_SHARE_COL).unwrap().f64().unwrap().get(2), None);
+    }
+
+    /// Kopfzeilen der Originaldateien 2018 und 2019 (samt Spalten ohne Entsprechung)
+    const HEADER_2018: &str = "Timestamp,Age,Gender,City,Position,Years of experience,Your level,Current Salary,\
+        Salary one year ago,Salary two years ago,Are you getting any Stock Options?,Main language at work,\
+        Company size,Company type";
+    const HEADER_2019: &str = "Zeitstempel,Age,Gender,City,Seniority level,Position (without seniority),\
+        Years of experience,Your main technology / programming language,\
+        Yearly brutto salary (without bonus and stocks),Yearly bonus,Yearly stocks,\
+        Yearly brutto salary (without bonus and stocks) one year ago. Only answer if staying in same country,\
+        Yearly bonus one year ago. Only answer if staying in same country,\
+        Yearly stocks one year ago. Only answer if staying in same country,Number of vacation days,\
+        Number of home office days per month,Main language at work,Company name ,Company size,Company type,\
+        Сontract duration,Company business sector";
+
+    fn load_year(csv: &str, year: i32) -> PolarsResult<(DataFrame, ValidationReport)> {
+        load_with_schema(csv.as_bytes(), "test.csv", &YearSchema::for_year(year).unwrap())
+    }
+
+    #[test]
+    fn header_of_each_year_maps_to_the_common_schema() {
+        for (year, header) in [(2018, HEADER_2018), (2019, HEADER_2019)] {
+            let (df, report) = load_year(header, year).unwrap_or_else(|e| panic!("{year}: {e}"));
+            assert_eq!((df.height(), report.total_rows), (0, 0));
+            let names = df.get_column_names();
+            for (col, _) in YearSchema::for_year(year).unwrap().columns {
+                assert!(names.iter().any(|n| *n == col.header()), "{year}: {} fehlt", col.header());
+            }
+        }
+        let without_stocks = HEADER_2019.replace(",Yearly stocks,", ",");
+        assert!(load_year(&without_stocks, 2019).is_err());
+    }
+
+    #[test]
+    fn stocks_2019_count_towards_the_bonus() {
+        let row = |bonus: &str, stocks: &str| {
+            format!("01.12.2019,30,Male,Berlin,Senior,Backend,5,Java,70000,{bonus},{stocks},65000,,1000,28,,English,,100-1000,Product,unlimited,")
+        };
+        let csv = [HEADER_2019.to_string(), row("5000", "3000"), row("", "2000"), row("4000", "some")].join("\n");
+        let (mut df, report) = load_year(&csv, 2019).unwrap();
+        let bonus = column(&df, Col::Bonus).unwrap().f64().unwrap().to_vec();
+        assert_eq!(bonus, [Some(8000.0), Some(2000.0), Some(4000.0)]);
+        let bonus_last_year = column(&df, Col::BonusLastYear).unwrap().f64().unwrap().to_vec();
+        assert_eq!(bonus_last_year, [Some(1000.0); 3]);
+        assert_eq!((report.valid_rows, report.warnings.len()), (3, 1));
+
+        add_compensation_columns(&mut df).unwrap();
+        let total = df.column(TOTAL_COMPENSATION_COL).unwrap().f64().unwrap().get(0);
+        assert_eq!(total, Some(78_000.0));
+    }
+}
@@ -0,0 +1,282 @@
+// src/datasets.rs
+
+use crate::error::{AppError, AppResult};
+use crate::validation::ValidationReport;
+use polars::prelude::*;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Zusammengeführte, aufbereitete Jahrgänge; gilt ohne `dataset`-Parameter
+pub const DEFAULT_DATASET: &str = "prepared";
+
+/// Höchstzahl gleichzeitig gehaltener Uploads und Varianten; darüber wird der älteste entfernt
+pub const MAX_USER_DATASETS: usize = 10;
+
+/// Uploads und Varianten verfallen nach dieser Zeit (Sekunden)
+pub const USER_DATASET_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Name eines Jahrgangs, wie er aus der Quelldatei geladen wurde
+pub fn unprepared_dataset_name(year: i32) -> String {
+    format!("unprepared-{year}")
+}
+
+/// Name einer Aufbereitungsvariante
+pub fn variant_dataset_name(name: &str) -> String {
+    format!("variant-{name}")
+}
+
+/// Herkunft eines Datensatzes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DatasetKind {
+    /// Alle Jahrgänge nach `prepare_survey` (Ableitungen, Imputation, Ausreißer-Regeln)
+    Prepared,
+    /// Ein Jahrgang aus seiner Quelldatei (z. B. cleaned_data.csv), geprüft, aber vor `prepare_survey`
+    Unprepared,
+    /// Hochgeladene CSV-Datei, aufbereitet wie die Quelldateien
+    Upload,
+    /// Die `Unprepared`-Jahrgänge, aufbereitet mit eigenen Imputations- und Ausreißer-Regeln
+    Variant,
+}
+
+impl DatasetKind {
+    /// Über die API angelegt und daher begrenzt und befristet
+    pub fn is_user_created(&self) -> bool {
+        matches!(self, DatasetKind::Upload | DatasetKind::Variant)
+    }
+
+    /// Kurzbeschreibung für `/datasets`
+    pub fn description(&self) -> &'static str {
+        match self {
+            DatasetKind::Prepared => "alle Jahrgänge, aufbereitet (Ableitungen, Imputation, Ausreißer-Regeln)",
+            DatasetKind::Unprepared => "ein Jahrgang wie aus der Quelldatei geladen, vor der Aufbereitung",
+            DatasetKind::Upload => "hochgeladene Datei, aufbereitet wie die Quelldateien",
+            DatasetKind::Variant => "alle Jahrgänge, aufbereitet mit eigenen Regeln zum Vergleich",
+        }
+    }
+}
+
+/// Beschreibung eines Datensatzes für `/datasets`
+#[derive(Debug, Clone, Serialize)]
+pub struct DatasetInfo {
+    pub name: String,
+    pub kind: DatasetKind,
+    pub description: &'static str,
+    /// Quelldateien bzw. Name der hochgeladenen Datei
+    pub source: Vec<String>,
+    pub rows: usize,
+    pub columns: usize,
+    /// Ladezeitpunkt (Unix-Sekunden)
+    pub loaded_at: u64,
+    /// Angewendete Bereinigungs-, Imputations- und Ausreißer-Schritte
+    pub cleaning_steps: Vec<String>,
+}
+
+/// Ein benannter Datensatz mit seinen Validierungsberichten
+#[derive(Debug, Clone)]
+pub struct Dataset {
+    pub df: DataFrame,
+    pub info: DatasetInfo,
+    pub reports: Vec<ValidationReport>,
+}
+
+impl Dataset {
+    pub fn new(name: &str, kind: DatasetKind, df: DataFrame, source: Vec<String>, cleaning_steps: Vec<String>, reports: Vec<ValidationReport>) -> Self {
+        let (rows, columns) = df.shape();
+        let loaded_at = unix_now();
+        Dataset {
+            df,
+            info: DatasetInfo {
+                name: name.to_string(),
+                kind,
+                description: kind.description(),
+                source,
+                rows,
+                columns,
+                loaded_at,
+                cleaning_steps,
+            },
+            reports,
+        }
+    }
+
+    /// Upload oder Variante, die älter als `USER_DATASET_TTL_SECS` ist
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.info.kind.is_user_created() && now.saturating_sub(self.info.loaded_at) > USER_DATASET_TTL_SECS
+    }
+
+    /// Metadaten plus Spalten mit Typ und Anzahl fehlender Werte
+    pub fn describe(&self) -> serde_json::Value {
+        let columns = self
+            .df
+            .get_columns()
+            .iter()
+            .map(|c| json!({
+                "name": c.name().as_str(),
+                "dtype": c.dtype().to_string(),
+                "null_count": c.null_count(),
+            }))
+            .collect::<Vec<_>>();
+        let dropped_rows = self.reports.iter().map(|r| r.dropped_rows).sum::<usize>();
+        json!({
+            "info": self.info,
+            "dropped_rows": dropped_rows,
+            "schema": columns,
+        })
+    }
+}
+
+/// Alle geladenen Datensätze nach Namen
+#[derive(Debug, Clone, Default)]
+pub struct DatasetRegistry {
+    datasets: BTreeMap<String, Dataset>,
+    next_upload_id: usize,
+}
+
+impl DatasetRegistry {
+    /// Datensatz hinzufügen oder gleichnamigen ersetzen
+    pub fn insert(&mut self, dataset: Dataset) {
+        self.datasets.insert(dataset.info.name.clone(), dataset);
+    }
+
+    /// Upload oder Variante hinzufügen. Abgelaufene werden vorher entfernt, bei mehr als
+    /// `MAX_USER_DATASETS` außerdem die ältesten; zurück kommen die Namen der entfernten.
+    pub fn insert_user(&mut self, dataset: Dataset) -> Vec<String> {
+        let mut removed = self.expire(unix_now());
+        self.datasets.remove(&dataset.info.name);
+        loop {
+            let user_created = self.datasets.values().filter(|d| d.info.kind.is_user_created());
+            if user_created.clone().count() < MAX_USER_DATASETS {
+                break;
+            }
+            let oldest = user_created
+                .min_by_key(|d| (d.info.loaded_at, d.info.name.clone()))
+                .map(|d| d.info.name.clone());
+            if let Some(name) = oldest {
+                self.datasets.remove(&name);
+                removed.push(name);
+            }
+        }
+        self.insert(dataset);
+        removed
+    }
+
+    /// Abgelaufene Uploads und Varianten entfernen und ihre Namen zurückgeben
+    pub fn expire(&mut self, now: u64) -> Vec<String> {
+        let expired = self
+            .datasets
+            .values()
+            .filter(|d| d.is_expired(now))
+            .map(|d| d.info.name.clone())
+            .collect::<Vec<String>>();
+        for name in &expired {
+            self.datasets.remove(name);
+        }
+        expired
+    }
+
+    /// Datensatz nach Namen; ohne Namen der Standarddatensatz. Abgelaufene gelten als unbekannt.
+    pub fn get(&self, name: Option<&str>) -> AppResult<&Dataset> {
+        let name = name.unwrap_or(DEFAULT_DATASET);
+        self.datasets
+            .get(name)
+            .filter(|d| !d.is_expired(unix_now()))
+            .ok_or_else(|| AppError::UnknownDataset(name.to_string()))
+    }
+
+    /// Datensatz entfernen; der Standarddatensatz bleibt immer erhalten
+    pub fn remove(&mut self, name: &str) -> AppResult<Dataset> {
+        if name == DEFAULT_DATASET {
+            return Err(AppError::InvalidParameter(format!(
+                "'{DEFAULT_DATASET}' ist der Standarddatensatz und kann nicht gelöscht werden"
+            )));
+        }
+        self.datasets
+            .remove(name)
+            .ok_or_else(|| AppError::UnknownDataset(name.to_string()))
+    }
+
+    /// Alle Datensätze entfernen, deren Art `keep` nicht erfüllt
+    pub fn retain(&mut self, keep: impl Fn(DatasetKind) -> bool) {
+        self.datasets.retain(|_, dataset| keep(dataset.info.kind));
+    }
+
+    /// Alle Datensätze einer Art, nach Namen sortiert
+    pub fn of_kind(&self, kind: DatasetKind) -> impl Iterator<Item = &Dataset> {
+        self.datasets.values().filter(move |d| d.info.kind == kind)
+    }
+
+    pub fn list(&self) -> Vec<DatasetInfo> {
+        let now = unix_now();
+        self.datasets
+            .values()
+            .filter(|d| !d.is_expired(now))
+            .map(|d| d.info.clone())
+            .collect()
+    }
+
+    /// Freien Namen `uploaded-<id>` für einen Upload vergeben
+    pub fn next_upload_name(&mut self) -> String {
+        self.next_upload_id += 1;
+        format!("uploaded-{}", self.next_upload_id)
+    }
+}
+
+/// Aktuelle Zeit in Unix-Sekunden
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dataset(name: &str, kind: DatasetKind, loaded_at: u64) -> Dataset {
+        let df = df!("salary" => [50_000.0]).unwrap();
+        let mut dataset = Dataset::new(name, kind, df, Vec::new(), Vec::new(), Vec::new());
+        dataset.info.loaded_at = loaded_at;
+        dataset
+    }
+
+    #[test]
+    fn oldest_user_datasets_are_removed_above_the_cap() {
+        let now = unix_now();
+        let mut registry = DatasetRegistry::default();
+        registry.insert(dataset(DEFAULT_DATASET, DatasetKind::Prepared, 0));
+        for i in 0..MAX_USER_DATASETS {
+            let removed = registry.insert_user(dataset(&format!("uploaded-{i}"), DatasetKind::Upload, now - 100 + i as u64));
+            assert!(removed.is_empty());
+        }
+        // Gleicher Name ersetzt nur
+        assert!(registry.insert_user(dataset("uploaded-5", DatasetKind::Upload, now)).is_empty());
+
+        let removed = registry.insert_user(dataset(&variant_dataset_name("a"), DatasetKind::Variant, now));
+        assert_eq!(removed, ["uploaded-0"]);
+        assert!(registry.get(Some("uploaded-0")).is_err());
+        assert!(registry.get(Some("variant-a")).is_ok());
+        assert_eq!(registry.list().len(), MAX_USER_DATASETS + 1);
+    }
+
+    #[test]
+    fn expired_user_datasets_disappear() {
+        let now = unix_now();
+        let old = now - USER_DATASET_TTL_SECS - 1;
+        let mut registry = DatasetRegistry::default();
+        registry.insert(dataset(DEFAULT_DATASET, DatasetKind::Prepared, old));
+        registry.insert(dataset(&unprepared_dataset_name(2020), DatasetKind::Unprepared, old));
+        registry.insert(dataset("uploaded-1", DatasetKind::Upload, old));
+        registry.insert(dataset("uploaded-2", DatasetKind::Upload, now));
+
+        assert!(matches!(registry.get(Some("uploaded-1")), Err(AppError::UnknownDataset(_))));
+        assert!(registry.get(None).is_ok());
+        assert_eq!(registry.list().len(), 3);
+
+        let removed = registry.insert_user(dataset("uploaded-3", DatasetKind::Upload, now));
+        assert_eq!(removed, ["uploaded-1"]);
+        assert_eq!(registry.of_kind(DatasetKind::Upload).count(), 2);
+    }
+}
@@ -0,0 +1,188 @@
+// src/density.rs
+
+use crate::data_analysis::{grouped_values, numeric_series, quantile};
+use crate::error::{AppError, AppResult};
+use polars::prelude::*;
+use serde_json::json;
+
+/// Standardanzahl der Gitterpunkte
+pub const DEFAULT_GRID_POINTS: usize = 200;
+
+/// Obergrenze der Gitterpunkte
+pub const MAX_GRID_POINTS: usize = 5_000;
+
+/// Kern der Dichteschätzung
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kernel {
+    Gaussian,
+    /// Träger [-1, 1], optimal im Sinne des mittleren quadratischen Fehlers
+    Epanechnikov,
+}
+
+impl Kernel {
+    pub fn parse(name: &str) -> Result<Kernel, String> {
+        match name.trim().to_lowercase().as_str() {
+            "gaussian" | "gauss" | "normal" => Ok(Kernel::Gaussian),
+            "epanechnikov" | "epa" => Ok(Kernel::Epanechnikov),
+            other => Err(format!("Kern '{other}' ist unbekannt (gaussian, epanechnikov)")),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Kernel::Gaussian => "gaussian",
+            Kernel::Epanechnikov => "epanechnikov",
+        }
+    }
+
+    fn weight(&self, u: f64) -> f64 {
+        match self {
+            Kernel::Gaussian => (-0.5 * u * u).exp() / (2.0 * std::f64::consts::PI).sqrt(),
+            Kernel::Epanechnikov if u.abs() <= 1.0 => 0.75 * (1.0 - u * u),
+            Kernel::Epanechnikov => 0.0,
+        }
+    }
+
+    /// Abstand in Bandbreiten, ab dem ein Wert praktisch nichts mehr beiträgt
+    fn reach(&self) -> f64 {
+        match self {
+            Kernel::Gaussian => 5.0,
+            Kernel::Epanechnikov => 1.0,
+        }
+    }
+}
+
+/// Wahl der Bandbreite
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bandwidth {
+    /// 0.9 · min(σ, IQR / 1.34) · n^(-1/5)
+    Silverman,
+    /// 1.06 · σ · n^(-1/5)
+    Scott,
+    Manual(f64),
+}
+
+impl Bandwidth {
+    /// `silverman`, `scott` oder eine positive Zahl
+    pub fn parse(spec: &str) -> Result<Bandwidth, String> {
+        match spec.trim().to_lowercase().as_str() {
+            "silverman" => Ok(Bandwidth::Silverman),
+            "scott" => Ok(Bandwidth::Scott),
+            other => match other.parse::<f64>() {
+                Ok(h) if h.is_finite() && h > 0.0 => Ok(Bandwidth::Manual(h)),
+                _ => Err(format!("Bandbreite '{other}' ist weder 'silverman', 'scott' noch eine Zahl größer als 0")),
+            },
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Bandwidth::Silverman => "silverman",
+            Bandwidth::Scott => "scott",
+            Bandwidth::Manual(_) => "manual",
+        }
+    }
+
+    /// Bandbreite für die Werte. Ohne Streuung gibt es keine sinnvolle Regel, dann gilt 1.
+    pub fn select(&self, values: &[f64]) -> f64 {
+        let n = values.len() as f64;
+        let std = || {
+            let mean = values.iter().sum::<f64>() / n;
+            (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0)).sqrt()
+        };
+        let h = match self {
+            Bandwidth::Manual(h) => return *h,
+            Bandwidth::Scott => 1.06 * std() * n.powf(-0.2),
+            Bandwidth::Silverman => {
+                let iqr = quantile(values, 0.75) - quantile(values, 0.25);
+                let spread = if iqr > 0.0 { std().min(iqr / 1.34) } else { std() };
+                0.9 * spread * n.powf(-0.2)
+            }
+        };
+        if h.is_finite() && h > 0.0 {
+            h
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Geschätzte Dichte an den Gitterpunkten (`values` sortiert)
+fn evaluate(sorted: &[f64], kernel: Kernel, h: f64, grid: &[f64]) -> Vec<f64> {
+    let n = sorted.len() as f64;
+    grid.iter()
+        .map(|&x| {
+            // Nur Werte innerhalb der Reichweite des Kerns betrachten
+            let from = sorted.partition_point(|&v| v < x - kernel.reach() * h);
+            let to = sorted.partition_point(|&v| v <= x + kernel.reach() * h);
+            sorted[from..to].iter().map(|&v| kernel.weight((x - v) / h)).sum::<f64>() / (n * h)
+        })
+        .collect()
+}
+
+/// Kerndichteschätzung einer numerischen Spalte auf einem gemeinsamen Gitter, mit `group_col`
+/// je Gruppe (Gruppen mit weniger als `min_count` Werten landen in "other"). Das Gitter reicht
+/// über die Daten hinaus, damit die Kurven an den Rändern auslaufen.
+pub fn calculate_density(
+    df: &DataFrame,
+    metric_col: &str,
+    group_col: Option<&str>,
+    kernel: Kernel,
+    bandwidth: Bandwidth,
+    points: usize,
+    min_count: usize,
+) -> AppResult<serde_json::Value> {
+    if !(2..=MAX_GRID_POINTS).contains(&points) {
+        return Err(AppError::InvalidParameter(format!(
+            "points muss zwischen 2 und {MAX_GRID_POINTS} liegen"
+        )));
+    }
+    let groups = match group_col {
+        Some(group_col) => grouped_values(df, group_col, metric_col, min_count)?,
+        None => vec![("all".to_string(), numeric_series(df, metric_col)?.into_iter().flatten().collect())],
+    };
+    let groups = groups
+        .into_iter()
+        .filter(|(_, values)| !values.is_empty())
+        .map(|(name, mut values)| {
+            values.sort_by(f64::total_cmp);
+            let h = bandwidth.select(&values);
+            (name, values, h)
+        })
+        .collect::<Vec<_>>();
+    if groups.is_empty() {
+        return Err(AppError::EmptyDataset(format!("keine Werte in '{metric_col}'")));
+    }
+
+    let lower = groups
+        .iter()
+        .map(|(_, values, h)| values[0] - kernel.reach() * h)
+        .fold(f64::INFINITY, f64::min);
+    let upper = groups
+        .iter()
+        .map(|(_, values, h)| values[values.len() - 1] + kernel.reach() * h)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let step = (upper - lower) / (points - 1) as f64;
+    let grid = (0..points).map(|i| lower + i as f64 * step).collect::<Vec<f64>>();
+
+    let groups = groups
+        .iter()
+        .map(|(name, values, h)| {
+            json!({
+                "name": name,
+                "count": values.len(),
+                "bandwidth": h,
+                "density": evaluate(values, kernel, *h, &grid),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(json!({
+        "column": metric_col,
+        "group_by": group_col,
+        "kernel": kernel.label(),
+        "bandwidth": bandwidth.label(),
+        "grid": grid,
+        "groups": groups,
+    }))
+}
@@ -0,0 +1,130 @@
+// src/error.rs
+
+use axum::{
+    extract::multipart::MultipartError,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use crate::validation::RowError;
+use polars::prelude::PolarsError;
+use rust_web_app::HistogramError;
+use serde_json::json;
+use std::fmt;
+
+/// Fehler der Analysefunktionen und Endpunkte
+#[derive(Debug)]
+pub enum AppError {
+    /// Spalte fehlt im DataFrame
+    MissingColumn(String),
+    /// Spalte hat nicht den erwarteten Typ
+    WrongType(String),
+    /// Keine Zeilen (z. B. nach einem Filter auf ein Jahr ohne Daten)
+    EmptyDataset(String),
+    /// Kein Datensatz mit diesem Namen
+    UnknownDataset(String),
+    /// Ungültiger Query-Parameter oder Formularwert
+    InvalidParameter(String),
+    /// Eingabedaten (z. B. ein Upload) sind nicht verwendbar
+    InvalidInput(String),
+    /// Neue Antwort verletzt Prüfregeln (einzeln in der Antwort aufgeführt)
+    InvalidRecord(Vec<RowError>),
+    /// Fehler beim Lesen eines Multipart-Uploads
+    Upload(MultipartError),
+    /// Sonstiger Fehler aus Polars
+    Data(PolarsError),
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    pub fn wrong_type(column: &str, expected: &str, found: impl fmt::Display) -> Self {
+        AppError::WrongType(format!("Spalte '{column}' ist vom Typ {found}, erwartet: {expected}"))
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            AppError::MissingColumn(_)
+            | AppError::WrongType(_)
+            | AppError::EmptyDataset(_)
+            | AppError::InvalidInput(_)
+            | AppError::InvalidRecord(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::UnknownDataset(_) => StatusCode::NOT_FOUND,
+            AppError::InvalidParameter(_) => StatusCode::BAD_REQUEST,
+            AppError::Upload(e) => e.status(),
+            AppError::Data(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Maschinenlesbare Fehlerart in der JSON-Antwort
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::MissingColumn(_) => "missing_column",
+            AppError::WrongType(_) => "wrong_type",
+            AppError::EmptyDataset(_) => "empty_dataset",
+            AppError::UnknownDataset(_) => "unknown_dataset",
+            AppError::InvalidParameter(_) => "invalid_parameter",
+            AppError::InvalidInput(_) => "invalid_input",
+            AppError::InvalidRecord(_) => "invalid_record",
+            AppError::Upload(_) => "upload",
+            AppError::Data(_) => "data_error",
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::MissingColumn(message) => write!(f, "{message}"),
+            AppError::WrongType(message) => write!(f, "{message}"),
+            AppError::EmptyDataset(message) => write!(f, "Keine Daten: {message}"),
+            AppError::UnknownDataset(name) => write!(f, "Datensatz '{name}' ist nicht geladen"),
+            AppError::InvalidParameter(message) => write!(f, "Ungültiger Parameter: {message}"),
+            AppError::InvalidInput(message) => write!(f, "{message}"),
+            AppError::InvalidRecord(errors) => {
+                let messages = errors.iter().map(|e| e.message.as_str()).collect::<Vec<_>>();
+                write!(f, "Antwort ungültig: {}", messages.join("; "))
+            }
+            AppError::Upload(e) => write!(f, "{}", e.body_text()),
+            AppError::Data(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<PolarsError> for AppError {
+    fn from(e: PolarsError) -> Self {
+        match e {
+            PolarsError::ColumnNotFound(message) => AppError::MissingColumn(message.to_string()),
+            PolarsError::NoData(message) => AppError::EmptyDataset(message.to_string()),
+            PolarsError::SchemaMismatch(message) => AppError::WrongType(message.to_string()),
+            e => AppError::Data(e),
+        }
+    }
+}
+
+impl From<HistogramError> for AppError {
+    fn from(e: HistogramError) -> Self {
+        match e {
+            HistogramError::Data(e) => e.into(),
+            HistogramError::Binning(message) => AppError::InvalidParameter(message),
+        }
+    }
+}
+
+impl From<MultipartError> for AppError {
+    fn from(e: MultipartError) -> Self {
+        AppError::Upload(e)
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let mut body = json!({ "error": self.kind(), "message": self.to_string() });
+        if let AppError::InvalidRecord(errors) = &self {
+            body["errors"] = json!(errors);
+        }
+        (self.status(), Json(body)).into_response()
+    }
+}
@@ -0,0 +1,167 @@
+// src/gazetteer.rs
+
+use crate::columns::{column, normalize_header, Col};
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::OnceLock;
+
+/// Mitgeliefertes Ortsverzeichnis (wird beim Start durch die Datei ersetzt, falls vorhanden)
+const BUNDLED_CITIES: &str = include_str!("../data/cities_de.csv");
+
+/// Abgeleitete Spalte mit dem vereinheitlichten Ortsnamen
+pub const CITY_NORMALIZED_COL: &str = "city_normalized";
+/// Abgeleitete Spalte mit dem Bundesland (null außerhalb Deutschlands)
+pub const STATE_COL: &str = "state";
+/// Abgeleitete Spalte mit der Region (Nord, Süd, West, Ost, Ausland)
+pub const REGION_COL: &str = "region";
+
+/// Ortsnamen vergleichbar machen: wie Spaltennamen, zusätzlich ohne
+/// Umlaute und ASCII-Satzzeichen ("Riga, Latvia" == "riga latvia", "Frankfurt a.M." == "frankfurt a m")
+fn normalize_city(raw: &str) -> String {
+    let folded = normalize_header(raw)
+        .replace('ä', "a")
+        .replace('ö', "o")
+        .replace('ü', "u")
+        .replace('ß', "ss")
+        .replace(|c: char| c.is_ascii_punctuation(), " ");
+    normalize_header(&folded)
+}
+
+/// Eintrag im Ortsverzeichnis
+#[derive(Debug, Clone)]
+pub struct Place {
+    pub city: String,
+    pub state: Option<String>,
+    pub region: Option<String>,
+}
+
+/// Ortsverzeichnis mit allen bekannten Schreibweisen
+#[derive(Debug, Clone, Default)]
+pub struct Gazetteer {
+    places: HashMap<String, Place>,
+}
+
+impl Gazetteer {
+    /// Verzeichnis im Format `city,aliases,state,region` lesen (`#` leitet Kommentare ein)
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, csv::Error> {
+        let mut csv_reader = csv::ReaderBuilder::new()
+            .comment(Some(b'#'))
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+
+        let mut places = HashMap::new();
+        for row in csv_reader.records() {
+            let row = row?;
+            let field = |i: usize| row.get(i).filter(|v| !v.is_empty()).map(str::to_string);
+            let city = row.get(0).unwrap_or_default();
+            let place = Place {
+                city: city.to_string(),
+                state: field(2),
+                region: field(3),
+            };
+
+            let aliases = row.get(1).unwrap_or_default();
+            for name in std::iter::once(city).chain(aliases.split('|')) {
+                if !name.trim().is_empty() {
+                    places.insert(normalize_city(name), place.clone());
+                }
+            }
+        }
+        Ok(Gazetteer { places })
+    }
+
+    /// Das mitgelieferte Verzeichnis
+    pub fn bundled() -> Self {
+        Self::from_reader(BUNDLED_CITIES.as_bytes()).expect("data/cities_de.csv ist ungültig")
+    }
+
+    pub fn lookup(&self, raw: &str) -> Option<&Place> {
+        self.places.get(&normalize_city(raw))
+    }
+}
+
+static GAZETTEER: OnceLock<Gazetteer> = OnceLock::new();
+
+/// Ortsverzeichnis einmalig beim Start festlegen
+pub fn init_gazetteer(gazetteer: Gazetteer) {
+    let _ = GAZETTEER.set(gazetteer);
+}
+
+/// Das prozessweite Ortsverzeichnis
+pub fn gazetteer() -> &'static Gazetteer {
+    GAZETTEER.get_or_init(Gazetteer::bundled)
+}
+
+/// Spalten `city_normalized`, `state` und `region` anhängen.
+/// Unbekannte Orte behalten ihren (getrimmten) Namen, Bundesland und Region bleiben leer.
+pub fn add_location_columns(df: &mut DataFrame) -> PolarsResult<()> {
+    let gazetteer = gazetteer();
+    let cities = column(df, Col::City)?.str()?.clone();
+
+    let mut city_normalized = Vec::with_capacity(cities.len());
+    let mut states = Vec::with_capacity(cities.len());
+    let mut regions = Vec::with_capacity(cities.len());
+    for raw in cities.into_iter() {
+        let place = raw.and_then(|r| gazetteer.lookup(r));
+        city_normalized.push(match place {
+            Some(place) => Some(place.city.clone()),
+            None => raw.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()),
+        });
+        states.push(place.and_then(|p| p.state.clone()));
+        regions.push(place.and_then(|p| p.region.clone()));
+    }
+
+    df.with_column(Column::new(CITY_NORMALIZED_COL.into(), city_normalized))?;
+    df.with_column(Column::new(STATE_COL.into(), states))?;
+    df.with_column(Column::new(REGION_COL.into(), regions))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CITIES: &str = "\
+# Kommentar
+city,aliases,state,region
+München,Munich|Muenchen,Bayern,Süd
+Frankfurt am Main,Frankfurt|Frankfurt a.M.|Frankfurt/Main,Hessen,West
+Riga,Riga Latvia,,Ausland
+";
+
+    fn gazetteer() -> Gazetteer {
+        Gazetteer::from_reader(CITIES.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn names_and_aliases_ignore_case_umlauts_and_spacing() {
+        let gazetteer = gazetteer();
+        for raw in ["München", "  MUNICH ", "muenchen", "Munchen"] {
+            let place = gazetteer.lookup(raw).unwrap_or_else(|| panic!("'{raw}' nicht gefunden"));
+            assert_eq!((place.city.as_str(), place.state.as_deref()), ("München", Some("Bayern")));
+        }
+        assert!(gazetteer.lookup("Nürnberg").is_none());
+    }
+
+    #[test]
+    fn punctuation_does_not_matter() {
+        let gazetteer = gazetteer();
+        for raw in ["Frankfurt a.M.", "Frankfurt a. M.", "frankfurt a m", "Frankfurt/Main", "Frankfurt (Main)"] {
+            let place = gazetteer.lookup(raw).unwrap_or_else(|| panic!("'{raw}' nicht gefunden"));
+            assert_eq!(place.city, "Frankfurt am Main");
+        }
+        let riga = gazetteer.lookup("Riga, Latvia").unwrap();
+        assert_eq!((riga.state.as_deref(), riga.region.as_deref()), (None, Some("Ausland")));
+    }
+
+    #[test]
+    fn unknown_cities_keep_their_trimmed_name() {
+        let mut df = df!(Col::City.header() => [Some("Munich"), Some(" Entenhausen "), Some("  "), None]).unwrap();
+        add_location_columns(&mut df).unwrap();
+        let text = |name: &str| df.column(name).unwrap().str().unwrap().into_iter().collect::<Vec<_>>();
+        assert_eq!(text(CITY_NORMALIZED_COL), [Some("München"), Some("Entenhausen"), None, None]);
+        assert_eq!(text(STATE_COL), [Some("Bayern"), None, None, None]);
+        assert_eq!(text(REGION_COL), [Some("Süd"), None, None, None]);
+    }
+}
@@ -0,0 +1,541 @@
+// src/imputation.rs
+
+use crate::columns::{registry, Col};
+use crate::data_analysis::{mean, median};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+
+/// Endung der Indikatorspalten (`bonus_imputed` ist true, wo `bonus` ergänzt wurde)
+pub const IMPUTED_SUFFIX: &str = "_imputed";
+
+/// Wie fehlende Werte einer Spalte ergänzt werden
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum ImputeStrategy {
+    /// Fehlende Werte bleiben null
+    LeaveNull,
+    /// Fester Wert (Zahl für numerische, Text für Textspalten)
+    Constant { value: serde_json::Value },
+    /// Mittelwert, optional je Gruppe
+    Mean {
+        #[serde(default)]
+        group_by: Option<String>,
+    },
+    /// Median, optional je Gruppe (z. B. Bonus je Seniorität)
+    Median {
+        #[serde(default)]
+        group_by: Option<String>,
+    },
+    /// Häufigster Wert, optional je Gruppe
+    Mode {
+        #[serde(default)]
+        group_by: Option<String>,
+    },
+    /// Lineare Regression auf numerische Prädiktoren
+    Model { predictors: Vec<String> },
+}
+
+impl fmt::Display for ImputeStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImputeStrategy::LeaveNull => write!(f, "leave_null"),
+            ImputeStrategy::Constant { value } => write!(f, "constant {value}"),
+            ImputeStrategy::Mean { group_by: None } => write!(f, "mean"),
+            ImputeStrategy::Mean { group_by: Some(group) } => write!(f, "mean by '{group}'"),
+            ImputeStrategy::Median { group_by: None } => write!(f, "median"),
+            ImputeStrategy::Median { group_by: Some(group) } => write!(f, "median by '{group}'"),
+            ImputeStrategy::Mode { group_by: None } => write!(f, "mode"),
+            ImputeStrategy::Mode { group_by: Some(group) } => write!(f, "mode by '{group}'"),
+            ImputeStrategy::Model { predictors } => write!(f, "model ~ {}", predictors.join(" + ")),
+        }
+    }
+}
+
+/// Ergänzungsregel für eine Spalte.
+/// In JSON z. B. `{"column": "bonus", "strategy": "median", "group_by": "seniority"}`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ImputationPolicy {
+    /// Schlüssel, Alias oder Spaltenname
+    pub column: String,
+    #[serde(flatten)]
+    pub strategy: ImputeStrategy,
+}
+
+impl ImputationPolicy {
+    fn new(col: Col, strategy: ImputeStrategy) -> Self {
+        ImputationPolicy {
+            column: col.key().to_string(),
+            strategy,
+        }
+    }
+
+    /// Name der Indikatorspalte
+    pub fn indicator_column(&self) -> String {
+        format!("{}{IMPUTED_SUFFIX}", self.column)
+    }
+}
+
+/// Regeln, die beim Start nach der Bereinigung auf jeden Jahrgang angewendet werden
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImputationConfig {
+    pub policies: Vec<ImputationPolicy>,
+}
+
+impl ImputationConfig {
+    /// Konfiguration aus einer JSON-Datei `{"policies": [...]}` lesen
+    pub fn from_json_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_reader(File::open(path)?)?)
+    }
+}
+
+impl Default for ImputationConfig {
+    /// Optionale Angaben ergänzen, Vorjahreswerte bleiben leer. Alter, Erfahrung und Geschlecht
+    /// füllt bereits die Bereinigung (`CleaningPipeline::survey_2020`).
+    fn default() -> Self {
+        let seniority = Some(Col::Seniority.key().to_string());
+        ImputationConfig {
+            policies: vec![
+                ImputationPolicy::new(Col::Bonus, ImputeStrategy::Median { group_by: seniority }),
+                ImputationPolicy::new(Col::VacationDays, ImputeStrategy::Mode { group_by: None }),
+                // Leer heißt hier "kein Zuschuss"
+                ImputationPolicy::new(
+                    Col::WfhSupport,
+                    ImputeStrategy::Constant { value: serde_json::Value::from(0.0) },
+                ),
+                ImputationPolicy::new(Col::SalaryLastYear, ImputeStrategy::LeaveNull),
+                ImputationPolicy::new(Col::BonusLastYear, ImputeStrategy::LeaveNull),
+            ],
+        }
+    }
+}
+
+/// Protokoll einer angewendeten Regel
+#[derive(Debug, Clone, Serialize)]
+pub struct ImputationLog {
+    pub column: String,
+    pub strategy: String,
+    pub imputed: usize,
+    /// Danach noch fehlende Werte (z. B. ohne Prädiktoren)
+    pub remaining_nulls: usize,
+}
+
+/// Alle Regeln der Reihe nach anwenden
+pub fn impute(df: &mut DataFrame, policies: &[ImputationPolicy]) -> PolarsResult<Vec<ImputationLog>> {
+    policies.iter().map(|policy| apply_policy(df, policy)).collect()
+}
+
+/// Protokolle auf stdout ausgeben
+pub fn print_imputation_summary(logs: &[ImputationLog]) {
+    println!("=== Imputation ===");
+    for log in logs {
+        println!(
+            "{:<40} {:<30} ergänzt: {}, weiterhin leer: {}",
+            log.column, log.strategy, log.imputed, log.remaining_nulls
+        );
+    }
+}
+
+/// Anzahl ergänzter Werte je Spalte anhand der Indikatorspalten
+pub fn imputed_counts(df: &DataFrame) -> Vec<(String, usize)> {
+    df.get_columns()
+        .iter()
+        .filter_map(|c| {
+            let name = c.name().strip_suffix(IMPUTED_SUFFIX)?;
+            let flags = c.bool().ok()?;
+            Some((name.to_string(), flags.into_iter().filter(|f| *f == Some(true)).count()))
+        })
+        .collect()
+}
+
+fn apply_policy(df: &mut DataFrame, policy: &ImputationPolicy) -> PolarsResult<ImputationLog> {
+    let column = registry().resolve_name(df, &policy.column)?;
+    let original = df.column(&column)?.as_materialized_series().clone();
+    let missing = original.is_null();
+
+    let filled = match &policy.strategy {
+        ImputeStrategy::LeaveNull => original.clone(),
+        ImputeStrategy::Constant { value } => fill_constant(&original, value)?,
+        ImputeStrategy::Mean { group_by } => {
+            if !original.dtype().is_numeric() {
+                polars_bail!(InvalidOperation: "Mittelwert für die Textspalte '{}' nicht möglich", column);
+            }
+            let groups = group_keys(df, group_by.as_deref())?;
+            fill_numeric(&original, mean, &groups)?
+        }
+        ImputeStrategy::Median { group_by } => {
+            if !original.dtype().is_numeric() {
+                polars_bail!(InvalidOperation: "Median für die Textspalte '{}' nicht möglich", column);
+            }
+            let groups = group_keys(df, group_by.as_deref())?;
+            fill_numeric(&original, median, &groups)?
+        }
+        ImputeStrategy::Mode { group_by } => {
+            let groups = group_keys(df, group_by.as_deref())?;
+            if original.dtype() == &DataType::String {
+                fill_text_mode(&original, &groups)?
+            } else {
+                fill_numeric(&original, numeric_mode, &groups)?
+            }
+        }
+        ImputeStrategy::Model { predictors } => fill_model(df, &original, predictors)?,
+    };
+
+    let imputed_mask = &missing & &filled.is_not_null();
+    let imputed = imputed_mask.into_iter().filter(|m| *m == Some(true)).count();
+    let remaining_nulls = filled.null_count();
+    df.replace(&column, filled)?;
+    if policy.strategy != ImputeStrategy::LeaveNull {
+        df.with_column(imputed_mask.with_name(policy.indicator_column().into()).into_series())?;
+    }
+
+    Ok(ImputationLog {
+        column: policy.column.clone(),
+        strategy: policy.strategy.to_string(),
+        imputed,
+        remaining_nulls,
+    })
+}
+
+/// Gruppenschlüssel je Zeile als Text; ohne Gruppierung gehören alle Zeilen zu einer Gruppe
+fn group_keys(df: &DataFrame, group_by: Option<&str>) -> PolarsResult<Vec<Option<String>>> {
+    match group_by {
+        None => Ok(vec![Some(String::new()); df.height()]),
+        Some(group) => {
+            let name = registry().resolve_name(df, group)?;
+            let keys = df.column(&name)?.cast(&DataType::String)?;
+            Ok(keys.str()?.into_iter().map(|k| k.map(str::to_string)).collect())
+        }
+    }
+}
+
+fn fill_constant(original: &Series, value: &serde_json::Value) -> PolarsResult<Series> {
+    let name = original.name().clone();
+    match (original.dtype(), value) {
+        (DataType::String, serde_json::Value::String(text)) => Ok(original
+            .str()?
+            .into_iter()
+            .map(|v| Some(v.unwrap_or(text)))
+            .collect::<StringChunked>()
+            .with_name(name)
+            .into_series()),
+        (dtype, serde_json::Value::Number(number)) if dtype.is_numeric() => {
+            let number = number.as_f64().unwrap_or(f64::NAN);
+            let values = original.cast(&DataType::Float64)?;
+            let filled = values
+                .f64()?
+                .into_iter()
+                .map(|v| Some(v.unwrap_or(number)))
+                .collect::<Float64Chunked>()
+                .with_name(name)
+                .into_series();
+            filled.cast(dtype)
+        }
+        (dtype, value) => {
+            polars_bail!(SchemaMismatch: "Wert {} passt nicht zur Spalte '{}' ({})", value, original.name(), dtype)
+        }
+    }
+}
+
+/// Numerische Spalte je Gruppe mit `statistic` der vorhandenen Werte füllen.
+/// Gruppen ohne Werte und fehlende Gruppen erhalten den Wert über alle Zeilen.
+fn fill_numeric(
+    original: &Series,
+    statistic: impl Fn(&[f64]) -> f64,
+    groups: &[Option<String>],
+) -> PolarsResult<Series> {
+    let values = original.cast(&DataType::Float64)?;
+    let values = values.f64()?;
+
+    let mut by_group = HashMap::<&str, Vec<f64>>::new();
+    for (group, value) in groups.iter().zip(values) {
+        if let (Some(group), Some(value)) = (group, value) {
+            by_group.entry(group.as_str()).or_default().push(value);
+        }
+    }
+    let overall = statistic(&values.into_iter().flatten().collect::<Vec<f64>>());
+    let fill_values = by_group
+        .into_iter()
+        .map(|(group, values)| (group, statistic(&values)))
+        .collect::<HashMap<&str, f64>>();
+
+    let integer = original.dtype().is_integer();
+    let filled = groups
+        .iter()
+        .zip(values)
+        .map(|(group, value)| {
+            value.or_else(|| {
+                let fill = group
+                    .as_deref()
+                    .and_then(|g| fill_values.get(g).copied())
+                    .unwrap_or(overall);
+                let fill = if integer { fill.round() } else { fill };
+                Some(fill).filter(|f| f.is_finite())
+            })
+        })
+        .collect::<Float64Chunked>()
+        .with_name(original.name().clone())
+        .into_series();
+    filled.cast(original.dtype())
+}
+
+/// Häufigster Wert; bei Gleichstand der kleinste
+fn numeric_mode(values: &[f64]) -> f64 {
+    let mut counts = HashMap::<u64, usize>::new();
+    for value in values {
+        *counts.entry(value.to_bits()).or_insert(0) += 1;
+    }
+    counts
+        .into_iter()
+        .map(|(bits, count)| (count, f64::from_bits(bits)))
+        .max_by(|a, b| a.0.cmp(&b.0).then(b.1.total_cmp(&a.1)))
+        .map_or(f64::NAN, |(_, value)| value)
+}
+
+/// Häufigster Text; bei Gleichstand der alphabetisch erste
+fn text_mode<'a>(values: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let mut counts = HashMap::<&str, usize>::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+        .map(|(value, _)| value)
+}
+
+fn fill_text_mode(original: &Series, groups: &[Option<String>]) -> PolarsResult<Series> {
+    let values = original.str()?;
+
+    let mut by_group = HashMap::<&str, Vec<&str>>::new();
+    for (group, value) in groups.iter().zip(values) {
+        if let (Some(group), Some(value)) = (group, value) {
+            by_group.entry(group.as_str()).or_default().push(value);
+        }
+    }
+    let overall = text_mode(values.into_iter().flatten());
+    let fill_values = by_group
+        .into_iter()
+        .filter_map(|(group, values)| Some((group, text_mode(values.into_iter())?)))
+        .collect::<HashMap<&str, &str>>();
+
+    Ok(groups
+        .iter()
+        .zip(values)
+        .map(|(group, value)| {
+            value.or_else(|| group.as_deref().and_then(|g| fill_values.get(g).copied()).or(overall))
+        })
+        .collect::<StringChunked>()
+        .with_name(original.name().clone())
+        .into_series())
+}
+
+/// Lineare Regression (kleinste Quadrate) auf vollständigen Zeilen, dann Vorhersage
+/// für Zeilen ohne Zielwert. Fehlt ein Prädiktor, bleibt der Wert leer.
+fn fill_model(df: &DataFrame, original: &Series, predictors: &[String]) -> PolarsResult<Series> {
+    if !original.dtype().is_numeric() {
+        polars_bail!(InvalidOperation: "Modell für die Textspalte '{}' nicht möglich", original.name());
+    }
+    let target = original.cast(&DataType::Float64)?;
+    let target = target.f64()?;
+    let features = predictors
+        .iter()
+        .map(|p| {
+            let name = registry().resolve_name(df, p)?;
+            Ok(df.column(&name)?.cast(&DataType::Float64)?.f64()?.clone())
+        })
+        .collect::<PolarsResult<Vec<Float64Chunked>>>()?;
+
+    let row = |i: usize| -> Option<Vec<f64>> {
+        std::iter::once(Some(1.0))
+            .chain(features.iter().map(|f| f.get(i)))
+            .collect()
+    };
+
+    // Normalgleichungen X'X b = X'y aufstellen
+    let k = predictors.len() + 1;
+    let mut xtx = vec![vec![0.0; k]; k];
+    let mut xty = vec![0.0; k];
+    let mut n = 0;
+    for (i, y) in target.into_iter().enumerate() {
+        if let (Some(y), Some(x)) = (y, row(i)) {
+            for a in 0..k {
+                xty[a] += x[a] * y;
+                for b in 0..k {
+                    xtx[a][b] += x[a] * x[b];
+                }
+            }
+            n += 1;
+        }
+    }
+    if n < k {
+        polars_bail!(ComputeError: "Zu wenige vollständige Zeilen ({}) für das Modell von '{}'", n, original.name());
+    }
+    let coefficients = solve(xtx, xty).ok_or_else(|| {
+        polars_err!(ComputeError: "Prädiktoren für '{}' sind linear abhängig", original.name())
+    })?;
+
+    let integer = original.dtype().is_integer();
+    let filled = target
+        .into_iter()
+        .enumerate()
+        .map(|(i, y)| {
+            y.or_else(|| {
+                let x = row(i)?;
+                let prediction = x.iter().zip(&coefficients).map(|(x, b)| x * b).sum::<f64>();
+                Some(if integer { prediction.round() } else { prediction })
+            })
+        })
+        .collect::<Float64Chunked>()
+        .with_name(original.name().clone())
+        .into_series();
+    filled.cast(original.dtype())
+}
+
+/// Gauß-Elimination mit Spaltenpivotsuche; None bei singulärer Matrix
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let (done, rest) = a.split_at_mut(col + 1);
+        let pivot_row = &done[col];
+        for (offset, row) in rest.iter_mut().enumerate() {
+            let factor = row[col] / pivot_row[col];
+            for (value, pivot_value) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot_value;
+            }
+            b[col + 1 + offset] -= factor * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum = (row + 1..n).map(|c| a[row][c] * x[c]).sum::<f64>();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grouped() -> DataFrame {
+        df!(
+            Col::Seniority.header() => [Some("A"), Some("A"), Some("A"), Some("A"), Some("B"), Some("B"), None],
+            Col::Bonus.header() => [Some(10.0), Some(10.0), Some(40.0), None, Some(100.0), None, None],
+        )
+        .unwrap()
+    }
+
+    fn filled(strategy: ImputeStrategy) -> Vec<Option<f64>> {
+        let mut df = grouped();
+        impute(&mut df, &[ImputationPolicy::new(Col::Bonus, strategy)]).unwrap();
+        df.column(Col::Bonus.header()).unwrap().f64().unwrap().to_vec()
+    }
+
+    #[test]
+    fn grouped_mean_median_and_mode() {
+        let seniority = || Some(Col::Seniority.key().to_string());
+        // Gruppe A: 10, 10, 40; Gruppe B: 100; ohne Gruppe gilt der Wert über alle Zeilen
+        let mean = filled(ImputeStrategy::Mean { group_by: seniority() });
+        assert_eq!((mean[3], mean[5], mean[6]), (Some(20.0), Some(100.0), Some(40.0)));
+        let median = filled(ImputeStrategy::Median { group_by: seniority() });
+        assert_eq!((median[3], median[5], median[6]), (Some(10.0), Some(100.0), Some(25.0)));
+        let mode = filled(ImputeStrategy::Mode { group_by: seniority() });
+        assert_eq!((mode[3], mode[5], mode[6]), (Some(10.0), Some(100.0), Some(10.0)));
+        assert_eq!(filled(ImputeStrategy::Mean { group_by: None })[3], Some(40.0));
+    }
+
+    #[test]
+    fn text_mode_breaks_ties_alphabetically() {
+        let mut df = df!(Col::Gender.header() => [Some("Male"), Some("Female"), None]).unwrap();
+        impute(&mut df, &[ImputationPolicy::new(Col::Gender, ImputeStrategy::Mode { group_by: None })]).unwrap();
+        assert_eq!(df.column(Col::Gender.header()).unwrap().str().unwrap().get(2), Some("Female"));
+    }
+
+    #[test]
+    fn constant_matches_column_type() {
+        let mut df = df!(
+            Col::Gender.header() => [Some("Male"), None],
+            Col::VacationDays.header() => [Some(30i32), None],
+        )
+        .unwrap();
+        let policies = [
+            ImputationPolicy::new(Col::Gender, ImputeStrategy::Constant { value: serde_json::Value::from("Unknown") }),
+            ImputationPolicy::new(Col::VacationDays, ImputeStrategy::Constant { value: serde_json::Value::from(25) }),
+        ];
+        impute(&mut df, &policies).unwrap();
+        assert_eq!(df.column(Col::Gender.header()).unwrap().str().unwrap().get(1), Some("Unknown"));
+        let days = df.column(Col::VacationDays.header()).unwrap();
+        assert_eq!(days.dtype(), &DataType::Int32);
+        assert_eq!(days.i32().unwrap().get(1), Some(25));
+
+        // Text für eine Zahlenspalte passt nicht
+        let text_for_number = ImputationPolicy::new(
+            Col::VacationDays,
+            ImputeStrategy::Constant { value: serde_json::Value::from("many") },
+        );
+        assert!(impute(&mut df, &[text_for_number]).is_err());
+    }
+
+    #[test]
+    fn integer_columns_are_rounded() {
+        let mut df = df!(Col::Age.header() => [Some(1i32), Some(2), None]).unwrap();
+        impute(&mut df, &[ImputationPolicy::new(Col::Age, ImputeStrategy::Mean { group_by: None })]).unwrap();
+        let age = df.column(Col::Age.header()).unwrap();
+        assert_eq!(age.dtype(), &DataType::Int32);
+        // Mittelwert 1.5, kaufmännisch gerundet
+        assert_eq!(age.i32().unwrap().to_vec(), vec![Some(1), Some(2), Some(2)]);
+    }
+
+    #[test]
+    fn solves_linear_systems() {
+        let x = solve(vec![vec![2.0, 1.0], vec![1.0, 3.0]], vec![3.0, 5.0]).unwrap();
+        assert!((x[0] - 0.8).abs() < 1e-12 && (x[1] - 1.4).abs() < 1e-12, "{x:?}");
+        assert_eq!(solve(vec![vec![1.0, 2.0], vec![2.0, 4.0]], vec![1.0, 2.0]), None);
+    }
+
+    #[test]
+    fn model_fill_predicts_from_complete_rows() {
+        let experience = Col::Experience.key().to_string();
+        let mut df = df!(
+            Col::Experience.header() => [1.0, 2.0, 3.0, 4.0],
+            Col::Salary.header() => [Some(3.0), Some(5.0), None, Some(9.0)],
+            Col::ExperienceGermany.header() => [2.0, 4.0, 6.0, 8.0],
+        )
+        .unwrap();
+        let model = |predictors: Vec<String>| ImputationPolicy::new(Col::Salary, ImputeStrategy::Model { predictors });
+
+        // Linear abhängige Prädiktoren ergeben eine singuläre Matrix
+        let collinear = model(vec![experience.clone(), Col::ExperienceGermany.key().to_string()]);
+        assert!(impute(&mut df, &[collinear]).is_err());
+
+        impute(&mut df, &[model(vec![experience])]).unwrap();
+        let salary = df.column(Col::Salary.header()).unwrap().f64().unwrap().get(2).unwrap();
+        assert!((salary - 7.0).abs() < 1e-9, "{salary}");
+    }
+
+    #[test]
+    fn indicator_marks_imputed_cells_only() {
+        let mut df = grouped();
+        let policies = [
+            ImputationPolicy::new(Col::Bonus, ImputeStrategy::Median { group_by: None }),
+            ImputationPolicy::new(Col::Seniority, ImputeStrategy::LeaveNull),
+        ];
+        let logs = impute(&mut df, &policies).unwrap();
+        let indicator = df.column("bonus_imputed").unwrap().bool().unwrap().into_iter().collect::<Vec<_>>();
+        let expected = [false, false, false, true, false, true, true].map(Some).to_vec();
+        assert_eq!(indicator, expected);
+        assert_eq!((logs[0].imputed, logs[0].remaining_nulls), (3, 0));
+        // Ohne Ergänzung keine Indikatorspalte
+        assert!(df.column("seniority_imputed").is_err());
+        assert_eq!(imputed_counts(&df), vec![("bonus".to_string(), 3)]);
+    }
+}
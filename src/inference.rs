@@ -0,0 +1,627 @@
+// src/inference.rs
+
+use crate::data_analysis::{median, numeric_series};
+use crate::error::{AppError, AppResult};
+use polars::prelude::*;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// Mindestanzahl Werte je Gruppe für einen Test
+pub const MIN_TEST_GROUP_SIZE: usize = 2;
+
+/// Abbruchgenauigkeit der Reihen und Kettenbrüche
+const EPSILON: f64 = 1e-15;
+const MAX_ITERATIONS: usize = 500;
+
+/// ln Γ(x) für x > 0 (Lanczos, g = 7)
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Spiegelung: Γ(x) Γ(1 - x) = π / sin(πx)
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let sum = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |acc, (i, c)| acc + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// Regularisierte unvollständige Betafunktion I_x(a, b)
+pub fn incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    // Der Kettenbruch konvergiert schnell für x < (a + 1) / (a + b + 2), sonst Symmetrie nutzen
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - front * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+/// Kettenbruch der unvollständigen Betafunktion (modifizierter Lentz-Algorithmus)
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    let tiny = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < tiny {
+        d = tiny;
+    }
+    d = 1.0 / d;
+    let mut result = d;
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+        // Gerader Schritt
+        let numerator = m * (b - m) * x / ((a + m2 - 1.0) * (a + m2));
+        d = 1.0 + numerator * d;
+        d = if d.abs() < tiny { 1.0 / tiny } else { 1.0 / d };
+        c = 1.0 + numerator / c;
+        if c.abs() < tiny {
+            c = tiny;
+        }
+        result *= d * c;
+        // Ungerader Schritt
+        let numerator = -(a + m) * (a + b + m) * x / ((a + m2) * (a + m2 + 1.0));
+        d = 1.0 + numerator * d;
+        d = if d.abs() < tiny { 1.0 / tiny } else { 1.0 / d };
+        c = 1.0 + numerator / c;
+        if c.abs() < tiny {
+            c = tiny;
+        }
+        let delta = d * c;
+        result *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    result
+}
+
+/// Regularisierte obere unvollständige Gammafunktion Q(a, x) = 1 - P(a, x)
+pub fn upper_incomplete_gamma(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let front = (-x + a * x.ln() - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        // Reihe für P(a, x)
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        for n in 1..=MAX_ITERATIONS {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        1.0 - front * sum
+    } else {
+        // Kettenbruch für Q(a, x)
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut result = d;
+        for n in 1..=MAX_ITERATIONS {
+            let an = -(n as f64) * (n as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            result *= delta;
+            if (delta - 1.0).abs() < EPSILON {
+                break;
+            }
+        }
+        front * result
+    }
+}
+
+/// Zweiseitiger p-Wert der Standardnormalverteilung: P(|Z| ≥ |z|) = erfc(|z| / √2)
+pub fn normal_two_sided_p(z: f64) -> f64 {
+    upper_incomplete_gamma(0.5, z * z / 2.0)
+}
+
+/// Verteilungsfunktion der Standardnormalverteilung Φ(z)
+pub fn normal_cdf(z: f64) -> f64 {
+    let tail = normal_two_sided_p(z) / 2.0;
+    if z < 0.0 {
+        tail
+    } else {
+        1.0 - tail
+    }
+}
+
+/// Quantil der Standardnormalverteilung Φ⁻¹(p) (Acklam-Näherung mit einem Newton-Schritt)
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    let x = if p < 0.024_25 {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - 0.024_25 {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    };
+    // Newton-Schritt auf Φ(x) - p
+    let density = (-x * x / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt();
+    x - (normal_cdf(x) - p) / density
+}
+
+/// Zweiseitiger p-Wert der t-Verteilung mit `df` Freiheitsgraden
+pub fn t_two_sided_p(t: f64, df: f64) -> f64 {
+    incomplete_beta(df / (df + t * t), df / 2.0, 0.5)
+}
+
+/// Obere Wahrscheinlichkeit P(F ≥ f) der F-Verteilung
+pub fn f_survival(f: f64, df1: f64, df2: f64) -> f64 {
+    if f <= 0.0 {
+        return 1.0;
+    }
+    incomplete_beta(df2 / (df2 + df1 * f), df2 / 2.0, df1 / 2.0)
+}
+
+/// Obere Wahrscheinlichkeit P(X ≥ x) der Chi²-Verteilung
+pub fn chi_squared_survival(x: f64, df: f64) -> f64 {
+    upper_incomplete_gamma(df / 2.0, x / 2.0)
+}
+
+/// Mittelwert und Stichprobenvarianz (n - 1)
+fn mean_variance(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, variance)
+}
+
+/// Ränge aller Werte (bei Gleichstand gemittelt) und die Bindungskorrektur Σ(t³ - t)
+pub fn ranks(values: &[f64]) -> (Vec<f64>, f64) {
+    let mut order = (0..values.len()).collect::<Vec<usize>>();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+    let mut ranks = vec![0.0; values.len()];
+    let mut ties = 0.0;
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.0;
+        for &idx in &order[start..end] {
+            ranks[idx] = rank;
+        }
+        let t = (end - start) as f64;
+        ties += t * t * t - t;
+        start = end;
+    }
+    (ranks, ties)
+}
+
+/// Ergebnis von Welchs t-Test
+#[derive(Debug, Clone, Copy)]
+pub struct WelchTest {
+    pub t: f64,
+    pub df: f64,
+    pub p_value: f64,
+}
+
+/// Welchs t-Test (ungleiche Varianzen), zweiseitig
+pub fn welch_t_test(a: &[f64], b: &[f64]) -> WelchTest {
+    let (mean_a, var_a) = mean_variance(a);
+    let (mean_b, var_b) = mean_variance(b);
+    let (se_a, se_b) = (var_a / a.len() as f64, var_b / b.len() as f64);
+    let t = (mean_a - mean_b) / (se_a + se_b).sqrt();
+    let df = (se_a + se_b).powi(2)
+        / (se_a * se_a / (a.len() as f64 - 1.0) + se_b * se_b / (b.len() as f64 - 1.0));
+    WelchTest { t, df, p_value: t_two_sided_p(t, df) }
+}
+
+/// Ergebnis des Mann-Whitney-U-Tests
+#[derive(Debug, Clone, Copy)]
+pub struct MannWhitneyTest {
+    /// U der ersten Stichprobe
+    pub u: f64,
+    pub z: f64,
+    pub p_value: f64,
+    /// Rang-biseriale Korrelation, positiv wenn die erste Stichprobe größere Werte hat
+    pub rank_biserial: f64,
+}
+
+/// Mann-Whitney-U-Test, zweiseitig über die Normalapproximation mit Stetigkeits- und Bindungskorrektur
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> MannWhitneyTest {
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let combined = a.iter().chain(b).copied().collect::<Vec<f64>>();
+    let (ranks, ties) = ranks(&combined);
+    let rank_sum = ranks[..a.len()].iter().sum::<f64>();
+    let u = rank_sum - n1 * (n1 + 1.0) / 2.0;
+    let n = n1 + n2;
+    let mean_u = n1 * n2 / 2.0;
+    let sd_u = (n1 * n2 / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0)))).sqrt();
+    let z = if sd_u > 0.0 {
+        let diff = u - mean_u;
+        (diff.abs() - 0.5).max(0.0).copysign(diff) / sd_u
+    } else {
+        0.0
+    };
+    MannWhitneyTest {
+        u,
+        z,
+        p_value: if sd_u > 0.0 { normal_two_sided_p(z).min(1.0) } else { 1.0 },
+        rank_biserial: 2.0 * u / (n1 * n2) - 1.0,
+    }
+}
+
+/// Cohens d mit gepoolter Standardabweichung
+pub fn cohens_d(a: &[f64], b: &[f64]) -> f64 {
+    let (mean_a, var_a) = mean_variance(a);
+    let (mean_b, var_b) = mean_variance(b);
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let pooled = (((n1 - 1.0) * var_a + (n2 - 1.0) * var_b) / (n1 + n2 - 2.0)).sqrt();
+    (mean_a - mean_b) / pooled
+}
+
+/// Ergebnis der einfaktoriellen Varianzanalyse
+#[derive(Debug, Clone, Copy)]
+pub struct AnovaTest {
+    pub f: f64,
+    pub df_between: f64,
+    pub df_within: f64,
+    pub p_value: f64,
+    /// Anteil der erklärten Streuung (η²)
+    pub eta_squared: f64,
+}
+
+/// Einfaktorielle Varianzanalyse über zwei oder mehr Gruppen
+pub fn one_way_anova(groups: &[&[f64]]) -> AnovaTest {
+    let n = groups.iter().map(|g| g.len()).sum::<usize>() as f64;
+    let k = groups.len() as f64;
+    let grand_mean = groups.iter().flat_map(|g| g.iter()).sum::<f64>() / n;
+    let (mut between, mut within) = (0.0, 0.0);
+    for group in groups {
+        let mean = group.iter().sum::<f64>() / group.len() as f64;
+        between += group.len() as f64 * (mean - grand_mean).powi(2);
+        within += group.iter().map(|x| (x - mean).powi(2)).sum::<f64>();
+    }
+    let (df_between, df_within) = (k - 1.0, n - k);
+    let f = (between / df_between) / (within / df_within);
+    AnovaTest {
+        f,
+        df_between,
+        df_within,
+        p_value: f_survival(f, df_between, df_within),
+        eta_squared: between / (between + within),
+    }
+}
+
+/// Ergebnis des Kruskal-Wallis-Tests
+#[derive(Debug, Clone, Copy)]
+pub struct KruskalWallisTest {
+    pub h: f64,
+    pub df: f64,
+    pub p_value: f64,
+}
+
+/// Kruskal-Wallis-Test mit Bindungskorrektur, p-Wert über die Chi²-Näherung
+pub fn kruskal_wallis(groups: &[&[f64]]) -> KruskalWallisTest {
+    let combined = groups.iter().flat_map(|g| g.iter()).copied().collect::<Vec<f64>>();
+    let n = combined.len() as f64;
+    let (ranks, ties) = ranks(&combined);
+    let mut offset = 0;
+    let mut sum = 0.0;
+    for group in groups {
+        let rank_sum = ranks[offset..offset + group.len()].iter().sum::<f64>();
+        sum += rank_sum * rank_sum / group.len() as f64;
+        offset += group.len();
+    }
+    let correction = 1.0 - ties / (n * n * n - n);
+    let h = if correction > 0.0 { (12.0 / (n * (n + 1.0)) * sum - 3.0 * (n + 1.0)) / correction } else { 0.0 };
+    let df = groups.len() as f64 - 1.0;
+    KruskalWallisTest { h, df, p_value: chi_squared_survival(h, df) }
+}
+
+/// Holm-Bonferroni-Korrektur mehrerer p-Werte, Reihenfolge bleibt erhalten
+pub fn holm_adjust(p_values: &[f64]) -> Vec<f64> {
+    let m = p_values.len();
+    let mut order = (0..m).collect::<Vec<usize>>();
+    order.sort_by(|&a, &b| p_values[a].total_cmp(&p_values[b]));
+    let mut adjusted = vec![0.0; m];
+    let mut running = 0.0_f64;
+    for (i, &idx) in order.iter().enumerate() {
+        running = running.max(((m - i) as f64 * p_values[idx]).min(1.0));
+        adjusted[idx] = running;
+    }
+    adjusted
+}
+
+/// Vergleich einer numerischen Spalte zwischen den Werten von `group_col`: ANOVA und
+/// Kruskal-Wallis über alle Gruppen, paarweise Welch- und Mann-Whitney-Tests mit Effektstärken
+/// und Holm-korrigierten p-Werten. Gruppen mit weniger als `min_count` Werten werden nicht
+/// getestet, sondern unter `excluded` aufgeführt; `only` beschränkt den Vergleich auf bestimmte Gruppen.
+pub fn compare_groups(
+    df: &DataFrame,
+    metric_col: &str,
+    group_col: &str,
+    only: &[String],
+    min_count: usize,
+) -> AppResult<serde_json::Value> {
+    let group_column = df.column(group_col)?;
+    if group_column.dtype().is_nested() {
+        return Err(AppError::wrong_type(group_col, "einzelne Werte", group_column.dtype()));
+    }
+    let labels = group_column.cast(&DataType::String)?;
+    let labels = labels.str()?;
+    let values = numeric_series(df, metric_col)?;
+
+    let mut by_group = BTreeMap::<&str, Vec<f64>>::new();
+    for (label, value) in labels.into_iter().zip(&values) {
+        if let (Some(label), Some(value)) = (label, value) {
+            if only.is_empty() || only.iter().any(|g| g == label) {
+                by_group.entry(label).or_default().push(value);
+            }
+        }
+    }
+    if let Some(missing) = only.iter().find(|g| !by_group.contains_key(g.as_str())) {
+        return Err(AppError::InvalidParameter(format!(
+            "Gruppe '{missing}' hat keine Werte in '{group_col}'"
+        )));
+    }
+    let min_count = min_count.max(MIN_TEST_GROUP_SIZE);
+    let (mut groups, excluded): (Vec<_>, Vec<_>) = by_group.into_iter().partition(|(_, v)| v.len() >= min_count);
+    if groups.len() < 2 {
+        return Err(AppError::InvalidParameter(format!(
+            "mindestens zwei Gruppen mit je {min_count} Werten nötig, gefunden: {}",
+            groups.len()
+        )));
+    }
+    groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+
+    let samples = groups.iter().map(|(_, v)| v.as_slice()).collect::<Vec<&[f64]>>();
+    let anova = one_way_anova(&samples);
+    let kruskal = kruskal_wallis(&samples);
+
+    let mut pairs = Vec::new();
+    for i in 0..groups.len() {
+        for j in i + 1..groups.len() {
+            let (a, b) = (&groups[i].1, &groups[j].1);
+            pairs.push((i, j, welch_t_test(a, b), mann_whitney_u(a, b), cohens_d(a, b)));
+        }
+    }
+    let welch_holm = holm_adjust(&pairs.iter().map(|p| p.2.p_value).collect::<Vec<f64>>());
+    let mann_whitney_holm = holm_adjust(&pairs.iter().map(|p| p.3.p_value).collect::<Vec<f64>>());
+
+    Ok(json!({
+        "column": metric_col,
+        "group_by": group_col,
+        "groups": groups
+            .iter()
+            .map(|(name, values)| {
+                let (mean, variance) = mean_variance(values);
+                json!({
+                    "name": name,
+                    "count": values.len(),
+                    "mean": mean,
+                    "median": median(values),
+                    "std": variance.sqrt(),
+                })
+            })
+            .collect::<Vec<_>>(),
+        "excluded": excluded
+            .iter()
+            .map(|(name, values)| json!({ "name": name, "count": values.len() }))
+            .collect::<Vec<_>>(),
+        "anova": {
+            "f": anova.f,
+            "df_between": anova.df_between,
+            "df_within": anova.df_within,
+            "p_value": anova.p_value,
+            "eta_squared": anova.eta_squared,
+        },
+        "kruskal_wallis": {
+            "h": kruskal.h,
+            "df": kruskal.df,
+            "p_value": kruskal.p_value,
+        },
+        "pairwise": pairs
+            .iter()
+            .enumerate()
+            .map(|(idx, (i, j, welch, mw, d))| json!({
+                "a": groups[*i].0,
+                "b": groups[*j].0,
+                "welch_t": {
+                    "t": welch.t,
+                    "df": welch.df,
+                    "p_value": welch.p_value,
+                    "p_value_holm": welch_holm[idx],
+                },
+                "mann_whitney": {
+                    "u": mw.u,
+                    "z": mw.z,
+                    "p_value": mw.p_value,
+                    "p_value_holm": mann_whitney_holm[idx],
+                },
+                "cohens_d": d,
+                "rank_biserial": mw.rank_biserial,
+            }))
+            .collect::<Vec<_>>(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "erwartet {expected}, erhalten {actual} (Toleranz {tolerance})"
+        );
+    }
+
+    #[test]
+    fn gamma_function() {
+        assert_close(ln_gamma(0.5), std::f64::consts::PI.sqrt().ln(), 1e-13);
+        assert_close(ln_gamma(1.0), 0.0, 1e-13);
+        assert_close(ln_gamma(10.0), 362_880.0_f64.ln(), 1e-12);
+    }
+
+    #[test]
+    fn normal_distribution() {
+        assert_close(normal_two_sided_p(1.959_963_984_540_054), 0.05, 1e-12);
+        assert_close(normal_two_sided_p(1.96), 0.049_995_790_296_440_87, 1e-12);
+        assert_close(normal_two_sided_p(0.0), 1.0, 1e-12);
+        assert_close(normal_cdf(-1.0), 0.158_655_253_931_457_05, 1e-12);
+        assert_close(normal_quantile(0.975), 1.959_963_984_540_054, 1e-9);
+        assert_close(normal_quantile(0.001), -3.090_232_306_167_813_5, 1e-9);
+    }
+
+    #[test]
+    fn t_distribution() {
+        // Cauchy-Verteilung: P(|T| ≥ 1) = 0.5
+        assert_close(t_two_sided_p(1.0, 1.0), 0.5, 1e-12);
+        // Kritische Werte t(0.975; df)
+        assert_close(t_two_sided_p(2.228_138_851_986_274, 10.0), 0.05, 1e-10);
+        assert_close(t_two_sided_p(2.570_581_835_636_314, 5.0), 0.05, 1e-10);
+    }
+
+    #[test]
+    fn f_distribution() {
+        assert_close(f_survival(4.102_821_015_130_39, 2.0, 10.0), 0.05, 1e-10);
+        assert_close(f_survival(3.098_391_212_407_26, 3.0, 20.0), 0.05, 1e-9);
+    }
+
+    #[test]
+    fn chi_squared_distribution() {
+        assert_close(chi_squared_survival(3.841_458_820_694_124, 1.0), 0.05, 1e-12);
+        assert_close(chi_squared_survival(5.991_464_547_107_979, 2.0), 0.05, 1e-12);
+        assert_close(chi_squared_survival(7.814_727_903_251_178, 3.0), 0.05, 1e-11);
+    }
+
+    #[test]
+    fn welch_and_cohens_d() {
+        // Beispiel 1 aus dem Wikipedia-Artikel zu Welchs t-Test
+        let a = [27.5, 21.0, 19.0, 23.6, 17.0, 17.9, 16.9, 20.1, 21.9, 22.6, 23.1, 19.6, 19.0, 21.7, 21.4];
+        let b = [27.1, 22.0, 20.8, 23.4, 23.4, 23.5, 25.8, 22.0, 24.8, 20.2, 21.9, 22.1, 22.9, 20.5, 24.4];
+        let test = welch_t_test(&a, &b);
+        assert_close(test.t, -2.455_356_398_286, 1e-9);
+        assert_close(test.df, 24.988_529_290_231, 1e-9);
+        assert_close(test.p_value, 0.021_378, 1e-5);
+        assert_close(cohens_d(&a, &b), -0.896_569_390_704, 1e-9);
+    }
+
+    #[test]
+    fn mann_whitney() {
+        // Beispiel aus der R-Dokumentation von wilcox.test, asymptotisch mit Stetigkeitskorrektur
+        let x = [0.80, 0.83, 1.89, 1.04, 1.45, 1.38, 1.91, 1.64, 0.73, 1.46];
+        let y = [1.15, 0.88, 0.90, 0.74, 1.21];
+        let test = mann_whitney_u(&x, &y);
+        assert_close(test.u, 35.0, 1e-12);
+        assert_close(test.z, 1.163_507_627_822, 1e-9);
+        assert_close(test.p_value, 0.244_623_605_126_983, 1e-9);
+        assert_close(test.rank_biserial, 0.4, 1e-12);
+    }
+
+    #[test]
+    fn anova() {
+        // Muscheldaten aus der SciPy-Dokumentation von f_oneway
+        let tillamook = [0.0571, 0.0813, 0.0831, 0.0976, 0.0817, 0.0859, 0.0735, 0.0659, 0.0923, 0.0836];
+        let newport = [0.0873, 0.0662, 0.0672, 0.0819, 0.0749, 0.0649, 0.0835, 0.0725];
+        let petersburg = [0.0974, 0.1352, 0.0817, 0.1016, 0.0968, 0.1064, 0.105];
+        let magadan = [0.1033, 0.0915, 0.0781, 0.0685, 0.0677, 0.0697, 0.0764, 0.0689];
+        let tvarminne = [0.0703, 0.1026, 0.0956, 0.0973, 0.1039, 0.1045];
+        let test = one_way_anova(&[&tillamook, &newport, &petersburg, &magadan, &tvarminne]);
+        assert_close(test.f, 7.121_019_471_642_447, 1e-9);
+        assert_close(test.p_value, 0.000_281_224_231_453_454_4, 1e-12);
+    }
+
+    #[test]
+    fn kruskal() {
+        // Beispiel aus der R-Dokumentation von kruskal.test
+        let x = [2.9, 3.0, 2.5, 2.6, 3.2];
+        let y = [3.8, 2.7, 4.0, 2.4];
+        let z = [2.8, 3.4, 3.7, 2.2, 2.0];
+        let test = kruskal_wallis(&[&x, &y, &z]);
+        assert_close(test.h, 0.771_428_571_428_57, 1e-9);
+        assert_close(test.p_value, (-test.h / 2.0).exp(), 1e-12);
+        assert_close(test.p_value, 0.68, 5e-3);
+    }
+
+    #[test]
+    fn ties_in_ranks() {
+        let (ranks, ties) = ranks(&[1.0, 2.0, 2.0, 3.0]);
+        assert_eq!(ranks, vec![1.0, 2.5, 2.5, 4.0]);
+        assert_close(ties, 6.0, 1e-12);
+    }
+
+    #[test]
+    fn holm() {
+        let adjusted = holm_adjust(&[0.01, 0.04, 0.03]);
+        assert_close(adjusted[0], 0.03, 1e-12);
+        assert_close(adjusted[1], 0.06, 1e-12);
+        assert_close(adjusted[2], 0.06, 1e-12);
+    }
+}
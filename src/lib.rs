@@ -1,31 +1,49 @@
+pub mod binning;
+
+use binning::BinStrategy;
 use polars::prelude::*;
 use serde::Serialize;
 
 #[derive(Serialize)]
 pub struct HistogramData {
-    bins: Vec<f64>,   // X-Werte (z. B. Gehaltsklassen)
-    counts: Vec<u32>, // Y-Werte (Häufigkeit in jeder Klasse)
+    bins: Vec<f64>,    // Klassengrenzen (eine mehr als Klassen)
+    centers: Vec<f64>, // X-Werte (Klassenmitten)
+    counts: Vec<u32>,  // Y-Werte (Häufigkeit in jeder Klasse)
+    strategy: &'static str,
 }
 
-pub fn calculate_histogram(df: &DataFrame, column_name: &str, bin_size: f64) -> Result<HistogramData, PolarsError> {
-    let col = df.column(column_name)?.f64()?.into_no_null_iter().collect::<Vec<f64>>();
-    let max_val = col.iter().cloned().fold(0.0_f64, f64::max);
-    let bins_count = (max_val / bin_size).ceil() as usize;
-    let mut bins = vec![0.0; bins_count + 1];
-    let mut counts = vec![0; bins_count];
-
-    for &value in &col {
-        let index = (value / bin_size).floor() as usize;
-        if index < bins_count {
-            counts[index] += 1;
-        }
-    }
+/// Fehler beim Histogramm: Spalte nicht lesbar oder ungeeignete Klasseneinteilung
+#[derive(Debug)]
+pub enum HistogramError {
+    Data(PolarsError),
+    Binning(String),
+}
 
-    for i in 0..=bins_count {
-        bins[i] = i as f64 * bin_size;
+impl From<PolarsError> for HistogramError {
+    fn from(e: PolarsError) -> Self {
+        HistogramError::Data(e)
     }
-
-    Ok(HistogramData { bins, counts })
 }
 
+pub fn calculate_histogram(
+    df: &DataFrame,
+    column_name: &str,
+    strategy: &BinStrategy,
+) -> Result<HistogramData, HistogramError> {
+    // Hochgeladene Dateien liefern Ganzzahlen, daher vorher nach f64 casten
+    let col = df
+        .column(column_name)?
+        .cast(&DataType::Float64)?
+        .f64()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<f64>>();
+    let histogram = strategy.histogram(&col).map_err(HistogramError::Binning)?;
 
+    Ok(HistogramData {
+        bins: histogram.edges,
+        centers: histogram.centers,
+        counts: histogram.counts.into_iter().map(|c| c as u32).collect(),
+        strategy: histogram.strategy,
+    })
+}
@@ -1,34 +1,181 @@
+mod bootstrap;
+mod cache;
+mod cleaning;
+mod columns;
+mod compensation;
+mod correlations;
 mod data_analysis;
+mod datasets;
+mod density;
+mod error;
+mod gazetteer;
+mod imputation;
+mod inference;
+mod outliers;
+mod raises;
+mod reload;
+mod stats;
+mod storage;
 mod plots;
+mod survey_years;
+mod tech_taxonomy;
+mod validation;
 mod web_app;
 
+use std::path::Path;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use axum::Server;
-use axum::{Router, routing::get};
+use cache::{CacheKey, CACHE_SCHEMA_VERSION};
+use cleaning::{clean_raw_survey, CleaningPipeline};
+use columns::{init_registry, ColumnRegistry};
 use data_analysis::*;
+use survey_years::CURRENT_YEAR;
+use gazetteer::{init_gazetteer, Gazetteer};
+use imputation::ImputationConfig;
+use outliers::OutlierConfig;
+use datasets::DatasetRegistry;
+use rust_web_app::binning::BinStrategy;
+use reload::{spawn_watcher, ReloadStatus, SurveySources};
+use storage::import_surveys;
+use tech_taxonomy::{init_taxonomy, TechTaxonomy};
 use web_app::*;
-use web_app::{eda_summary, get_distribution_data, AppState};
 
+/// Wie oft die Quelldateien auf Änderungen geprüft werden
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let csv_path = "cleaned_data.csv";
     let image_path = "salary_histogram.png";
+    let aliases_path = "column_aliases.json";
+    let tech_synonyms_path = "data/tech_synonyms.csv";
+    let cities_path = "data/cities_de.csv";
+    let imputation_path = "imputation.json";
+    let outliers_path = "outliers.json";
+    let database_path = "survey.db";
 
-    println!("Lade Daten aus {csv_path}...");
-    let mut df = load_data(csv_path)?;
-    println!("Daten geladen. Shape: {:?}", df.shape());
+    // Zusätzliche Schreibweisen für Spaltennamen (optional)
+    let mut registry = ColumnRegistry::default();
+    if Path::new(aliases_path).exists() {
+        registry.load_aliases(aliases_path)?;
+        println!("Spalten-Aliase aus {aliases_path} geladen.");
+    }
+    init_registry(registry);
 
-    clean_data(&mut df)?;
-    println!("Nach clean_data: Shape: {:?}", df.shape());
+    // Synonymtabelle für Technologien: bearbeitete Datei vor mitgelieferter Fassung
+    if Path::new(tech_synonyms_path).exists() {
+        init_taxonomy(TechTaxonomy::from_reader(std::fs::File::open(tech_synonyms_path)?)?);
+        println!("Technologie-Synonyme aus {tech_synonyms_path} geladen.");
+    }
+    if Path::new(cities_path).exists() {
+        init_gazetteer(Gazetteer::from_reader(std::fs::File::open(cities_path)?)?);
+        println!("Ortsverzeichnis aus {cities_path} geladen.");
+    }
 
-    eda(&df);
+    // Ergänzung fehlender Werte; ohne Datei gelten die Standardregeln
+    let imputation_config = if Path::new(imputation_path).exists() {
+        println!("Imputationsregeln aus {imputation_path} geladen.");
+        ImputationConfig::from_json_file(imputation_path)?
+    } else {
+        ImputationConfig::default()
+    };
 
-    create_salary_histogram_and_save(&df, image_path)?;
+    // Ausreißer-Regeln für alle Jahrgänge; ohne Datei werden Gehälter nur markiert
+    let outlier_config = if Path::new(outliers_path).exists() {
+        println!("Ausreißer-Regeln aus {outliers_path} geladen.");
+        OutlierConfig::from_json_file(outliers_path)?
+    } else {
+        OutlierConfig::default()
+    };
+
+    // `rust_web_app clean [roh.csv] [ziel.csv] [pipeline.json]` erzeugt die bereinigte Datei neu
+    let args = std::env::args().collect::<Vec<String>>();
+    if args.get(1).map(String::as_str) == Some("clean") {
+        let raw_path = args.get(2).map(String::as_str).unwrap_or("IT_Salary_Survey_2020.csv");
+        let output_path = args.get(3).map(String::as_str).unwrap_or(csv_path);
+        let log_path = format!("{output_path}.log.json");
+        let pipeline = match args.get(4) {
+            Some(path) => CleaningPipeline::from_json_file(path)?,
+            None => CleaningPipeline::survey_2020(),
+        };
+        let log = clean_raw_survey(raw_path, output_path, &log_path, &pipeline)?;
+        log.print_summary();
+        println!("{output_path} und {log_path} geschrieben.");
+        return Ok(());
+    }
+
+    // Ältere Jahrgänge werden nur geladen, wenn ihre Dateien vorhanden sind
+    let survey_files = vec![
+        (2018, "IT_Salary_Survey_2018.csv".to_string()),
+        (2019, "IT_Salary_Survey_2019.csv".to_string()),
+        (CURRENT_YEAR, csv_path.to_string()),
+    ];
+
+    // `rust_web_app import-db` legt die Antwort-Datenbank an und importiert die CSV-Dateien,
+    // `rust_web_app query "<sql>"` gibt das Ergebnis einer Abfrage auf die Datenbank aus
+    match args.get(1).map(String::as_str) {
+        Some("import-db") => {
+            let years = import_surveys(database_path, &survey_files)?;
+            println!("{database_path} enthält {} Jahrgänge.", years.len());
+            return Ok(());
+        }
+        Some("query") => {
+            let sql = args.get(2).ok_or("Aufruf: rust_web_app query \"<sql>\"")?;
+            println!("{}", load_data_from_query(database_path, sql)?);
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // Gibt es die Datenbank, werden neue oder geänderte CSV-Dateien importiert (auch beim
+    // Neuladen) und alle Jahrgänge aus ihr geladen
+    let (survey_files, imports) = if Path::new(database_path).exists() {
+        (import_surveys(database_path, &survey_files)?, survey_files)
+    } else {
+        (survey_files, Vec::new())
+    };
+
+    // Cache-Schlüssel: alles, was das aufbereitete DataFrame beeinflusst, außer der Quelldatei
+    let mut config_key = CacheKey::default();
+    config_key
+        .add_str(env!("CARGO_PKG_VERSION"))
+        .add_str(&CACHE_SCHEMA_VERSION.to_string())
+        .add_str(&format!("{:?}", CleaningPipeline::survey_2020()))
+        .add_str(&format!("{:?}", imputation_config.policies))
+        .add_str(&format!("{:?}", outlier_config.rules));
+    config_key
+        .add_file(aliases_path)?
+        .add_file(tech_synonyms_path)?
+        .add_file(cities_path)?;
+
+    let sources = Arc::new(SurveySources {
+        files: survey_files,
+        imports,
+        config_key,
+        imputation: imputation_config.policies,
+        outliers: outlier_config.rules,
+    });
+    let surveys = sources.load().map_err(|e| e as Box<dyn std::error::Error>)?;
+    let df = &surveys.df;
+
+    eda(df);
+
+    create_salary_histogram_and_save(df, image_path, &BinStrategy::default())?;
 
     println!("Bild wurde erfolgreich unter {image_path} gespeichert.");
 
-    let shared_state = Arc::new(Mutex::new(AppState { df }));
+    let reload = ReloadStatus::new(&sources, surveys.data_hash.clone());
+    let mut datasets = DatasetRegistry::default();
+    surveys.register(&mut datasets);
+    let shared_state = Arc::new(Mutex::new(AppState {
+        datasets,
+        imputation_policies: sources.imputation.clone(),
+        outlier_rules: sources.outliers.clone(),
+        reload,
+        sources: sources.clone(),
+    }));
+    spawn_watcher(shared_state.clone(), sources, RELOAD_POLL_INTERVAL);
     let app = create_router(shared_state);
 
     let addr = "0.0.0.0:3000";
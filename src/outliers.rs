@@ -0,0 +1,447 @@
+// src/outliers.rs
+
+use crate::columns::{registry, Col};
+use crate::data_analysis::{median, quantile};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fmt;
+use std::fs::File;
+
+/// Faktor, mit dem die MAD bei Normalverteilung der Standardabweichung entspricht
+const MAD_SCALE: f64 = 1.4826;
+
+/// Üblicher Faktor für Tukey-Zäune
+pub const DEFAULT_IQR_K: f64 = 1.5;
+/// Üblicher Schwellenwert für robuste z-Werte
+pub const DEFAULT_MAD_THRESHOLD: f64 = 3.5;
+
+fn default_iqr_k() -> f64 {
+    DEFAULT_IQR_K
+}
+
+fn default_mad_threshold() -> f64 {
+    DEFAULT_MAD_THRESHOLD
+}
+
+/// Verfahren, mit dem die Grenzen für Ausreißer bestimmt werden
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum OutlierMethod {
+    /// Tukey-Zäune: [Q1 - k·IQR, Q3 + k·IQR]
+    Iqr {
+        #[serde(default = "default_iqr_k")]
+        k: f64,
+    },
+    /// Robuster z-Wert |x - Median| / (1,4826·MAD) größer als `threshold`
+    Mad {
+        #[serde(default = "default_mad_threshold")]
+        threshold: f64,
+    },
+    /// Feste Perzentile (0 bis 100) als Grenzen
+    Percentile { lower: f64, upper: f64 },
+}
+
+impl fmt::Display for OutlierMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutlierMethod::Iqr { k } => write!(f, "iqr (k = {k})"),
+            OutlierMethod::Mad { threshold } => write!(f, "mad (|z| > {threshold})"),
+            OutlierMethod::Percentile { lower, upper } => write!(f, "percentile [{lower}, {upper}]"),
+        }
+    }
+}
+
+impl OutlierMethod {
+    /// Parameter prüfen
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            OutlierMethod::Iqr { k } if !(k.is_finite() && k >= 0.0) => {
+                Err(format!("k muss >= 0 sein, ist {k}"))
+            }
+            OutlierMethod::Mad { threshold } if !(threshold.is_finite() && threshold > 0.0) => {
+                Err(format!("threshold muss > 0 sein, ist {threshold}"))
+            }
+            OutlierMethod::Percentile { lower, upper } if !(0.0 <= lower && lower < upper && upper <= 100.0) => {
+                Err(format!("Perzentile müssen 0 <= lower < upper <= 100 erfüllen, sind {lower} und {upper}"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Untere und obere Grenze für die gegebenen Werte (NaN bei leerer Eingabe).
+    /// Ist die MAD 0 (mehr als die Hälfte gleiche Werte), gilt nichts als Ausreißer.
+    pub fn fences(&self, values: &[f64]) -> (f64, f64) {
+        match *self {
+            OutlierMethod::Iqr { k } => {
+                let q1 = quantile(values, 0.25);
+                let q3 = quantile(values, 0.75);
+                let iqr = q3 - q1;
+                (q1 - k * iqr, q3 + k * iqr)
+            }
+            OutlierMethod::Mad { threshold } => {
+                let center = median(values);
+                let deviations = values.iter().map(|v| (v - center).abs()).collect::<Vec<f64>>();
+                let mad = median(&deviations) * MAD_SCALE;
+                if mad == 0.0 {
+                    return (f64::NEG_INFINITY, f64::INFINITY);
+                }
+                (center - threshold * mad, center + threshold * mad)
+            }
+            OutlierMethod::Percentile { lower, upper } => {
+                (quantile(values, lower / 100.0), quantile(values, upper / 100.0))
+            }
+        }
+    }
+}
+
+/// Umgang mit erkannten Ausreißern
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutlierAction {
+    /// Zeilen verwerfen
+    Drop,
+    /// Werte auf die Grenzen kappen
+    Winsorize,
+    /// Nur in der Spalte `<spalte>_outlier` markieren
+    Flag,
+}
+
+/// Ausreißer-Regel für eine Spalte.
+/// In JSON z. B. `{"column": "salary", "method": "iqr", "k": 1.5, "action": "flag"}`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct OutlierRule {
+    /// Schlüssel, Alias oder Spaltenname
+    pub column: String,
+    #[serde(flatten)]
+    pub method: OutlierMethod,
+    pub action: OutlierAction,
+}
+
+impl OutlierRule {
+    /// Regel aus einer Kurzform `methode[:parameter][:aktion]` lesen,
+    /// z. B. `iqr`, `mad:3:drop` oder `percentile:1-99:winsorize`.
+    /// Ohne Aktion wird gekappt.
+    pub fn parse(column: &str, spec: &str) -> Result<OutlierRule, String> {
+        let mut parts = spec.split(':').map(str::trim);
+        let name = parts.next().unwrap_or_default().to_lowercase();
+        let mut rest = parts.collect::<Vec<&str>>();
+
+        let action = match rest.last().map(|a| a.to_lowercase()).as_deref() {
+            Some("drop") => Some(OutlierAction::Drop),
+            Some("winsorize") => Some(OutlierAction::Winsorize),
+            Some("flag") => Some(OutlierAction::Flag),
+            _ => None,
+        };
+        if action.is_some() {
+            rest.pop();
+        }
+        if rest.len() > 1 {
+            return Err(format!("Zu viele Angaben in '{spec}'"));
+        }
+
+        let number = |text: &str| {
+            text.parse::<f64>()
+                .map_err(|_| format!("'{text}' ist keine Zahl"))
+        };
+        let method = match (name.as_str(), rest.first()) {
+            ("iqr", None) => OutlierMethod::Iqr { k: DEFAULT_IQR_K },
+            ("iqr", Some(k)) => OutlierMethod::Iqr { k: number(k)? },
+            ("mad", None) => OutlierMethod::Mad { threshold: DEFAULT_MAD_THRESHOLD },
+            ("mad", Some(t)) => OutlierMethod::Mad { threshold: number(t)? },
+            ("percentile", None) => OutlierMethod::Percentile { lower: 1.0, upper: 99.0 },
+            ("percentile", Some(range)) => {
+                let (lower, upper) = range
+                    .split_once('-')
+                    .ok_or_else(|| format!("Perzentile als 'unten-oben' angeben, nicht '{range}'"))?;
+                OutlierMethod::Percentile { lower: number(lower)?, upper: number(upper)? }
+            }
+            _ => return Err(format!("Unbekanntes Verfahren '{name}' (iqr, mad, percentile)")),
+        };
+        method.validate()?;
+
+        Ok(OutlierRule {
+            column: column.to_string(),
+            method,
+            action: action.unwrap_or(OutlierAction::Winsorize),
+        })
+    }
+
+    /// Name der Markierungsspalte bei `OutlierAction::Flag`
+    pub fn flag_column(&self) -> String {
+        format!("{}_outlier", self.column)
+    }
+}
+
+/// Regeln, die beim Start auf jeden Jahrgang angewendet werden
+#[derive(Debug, Clone, Deserialize)]
+pub struct OutlierConfig {
+    pub rules: Vec<OutlierRule>,
+}
+
+impl OutlierConfig {
+    /// Konfiguration aus einer JSON-Datei `{"rules": [...]}` lesen
+    pub fn from_json_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let config: OutlierConfig = serde_json::from_reader(File::open(path)?)?;
+        for rule in &config.rules {
+            rule.method.validate()?;
+        }
+        Ok(config)
+    }
+}
+
+impl Default for OutlierConfig {
+    /// Gehälter nach Tukey markieren, die Daten selbst bleiben unverändert
+    fn default() -> Self {
+        OutlierConfig {
+            rules: vec![OutlierRule {
+                column: Col::Salary.key().to_string(),
+                method: OutlierMethod::Iqr { k: DEFAULT_IQR_K },
+                action: OutlierAction::Flag,
+            }],
+        }
+    }
+}
+
+/// Ergebnis einer angewendeten Regel
+#[derive(Debug, Clone, Serialize)]
+pub struct OutlierSummary {
+    pub column: String,
+    pub method: String,
+    pub action: OutlierAction,
+    pub lower: f64,
+    pub upper: f64,
+    /// Verworfene Zeilen, gekappte oder markierte Werte
+    pub affected: usize,
+}
+
+impl OutlierSummary {
+    pub fn print_summary(&self) {
+        println!(
+            "Ausreißer in '{}' nach {}: Grenzen [{:.2}, {:.2}], {:?}: {}",
+            self.column, self.method, self.lower, self.upper, self.action, self.affected
+        );
+    }
+}
+
+/// Erkannte Ausreißer einer Spalte
+struct Detection {
+    column: String,
+    lower: f64,
+    upper: f64,
+    values: Float64Chunked,
+    /// true für Ausreißer, fehlende Werte sind nie Ausreißer
+    mask: BooleanChunked,
+}
+
+fn detect(df: &DataFrame, column: &str, method: &OutlierMethod) -> PolarsResult<Detection> {
+    let column = registry().resolve_name(df, column)?;
+    let dtype = df.column(&column)?.dtype();
+    if !dtype.is_numeric() {
+        polars_bail!(SchemaMismatch: "Spalte '{}' ist vom Typ {}, erwartet: numerisch", column, dtype);
+    }
+    let values = df.column(&column)?.cast(&DataType::Float64)?.f64()?.clone();
+    let present = values.into_iter().flatten().collect::<Vec<f64>>();
+    let (lower, upper) = method.fences(&present);
+    let mask = values
+        .into_iter()
+        .map(|v| v.is_some_and(|v| v < lower || v > upper))
+        .collect::<BooleanChunked>();
+    Ok(Detection { column, lower, upper, values, mask })
+}
+
+/// Eine Regel auf das DataFrame anwenden
+pub fn apply_rule(df: &mut DataFrame, rule: &OutlierRule) -> PolarsResult<OutlierSummary> {
+    let detection = detect(df, &rule.column, &rule.method)?;
+    let affected = detection.mask.into_iter().filter(|m| *m == Some(true)).count();
+
+    match rule.action {
+        OutlierAction::Drop => {
+            *df = df.filter(&!&detection.mask)?;
+        }
+        OutlierAction::Winsorize => {
+            let (lower, upper) = (detection.lower, detection.upper);
+            let capped = detection
+                .values
+                .apply_values(|v| if v < lower { lower } else if v > upper { upper } else { v })
+                .with_name(detection.column.as_str().into());
+            df.replace(&detection.column, capped.into_series())?;
+        }
+        OutlierAction::Flag => {
+            let flags = detection.mask.with_name(rule.flag_column().into());
+            df.with_column(flags.into_series())?;
+        }
+    }
+
+    Ok(OutlierSummary {
+        column: detection.column,
+        method: rule.method.to_string(),
+        action: rule.action,
+        lower: detection.lower,
+        upper: detection.upper,
+        affected,
+    })
+}
+
+/// Mehrere Regeln nacheinander anwenden
+pub fn apply_rules(df: &mut DataFrame, rules: &[OutlierRule]) -> PolarsResult<Vec<OutlierSummary>> {
+    rules.iter().map(|rule| apply_rule(df, rule)).collect()
+}
+
+/// Alle Zeilen, die nach `method` in `column` als Ausreißer gelten, mit Kontextspalten zur Durchsicht
+pub fn list_outliers(df: &DataFrame, column: &str, method: &OutlierMethod) -> PolarsResult<serde_json::Value> {
+    let detection = detect(df, column, method)?;
+
+    let context = [Col::Timestamp, Col::City, Col::Position, Col::Seniority, Col::Experience]
+        .into_iter()
+        .filter_map(|col| registry().resolve(df, col).ok().map(|name| (col.key(), name)))
+        .map(|(key, name)| {
+            let text = df.column(&name)?.cast(&DataType::String)?.str()?.clone();
+            Ok((key, text))
+        })
+        .collect::<PolarsResult<Vec<(&str, StringChunked)>>>()?;
+
+    let rows = detection
+        .mask
+        .into_iter()
+        .enumerate()
+        .filter(|(_, flagged)| *flagged == Some(true))
+        .map(|(row, _)| {
+            let mut entry = serde_json::Map::new();
+            entry.insert("row".to_string(), json!(row));
+            entry.insert("value".to_string(), json!(detection.values.get(row)));
+            for (key, text) in &context {
+                entry.insert(key.to_string(), json!(text.get(row)));
+            }
+            serde_json::Value::Object(entry)
+        })
+        .collect::<Vec<serde_json::Value>>();
+
+    Ok(json!({
+        "column": detection.column,
+        "method": method.to_string(),
+        "lower": detection.lower,
+        "upper": detection.upper,
+        "count": rows.len(),
+        "rows": rows,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "erwartet {expected}, erhalten {actual} (Toleranz {tolerance})"
+        );
+    }
+
+    /// 1 bis 9 und ein Extremwert
+    const VALUES: [f64; 10] = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 100.0];
+
+    fn frame() -> DataFrame {
+        df!(
+            Col::Salary.header() => VALUES,
+            Col::Bonus.header() => [0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0, 80.0, 90.0],
+        )
+        .unwrap()
+    }
+
+    fn rule(spec: &str) -> OutlierRule {
+        OutlierRule::parse(Col::Salary.key(), spec).unwrap()
+    }
+
+    #[test]
+    fn iqr_mad_and_percentile_fences() {
+        // Q1 = 3.25, Q3 = 7.75 (lineare Interpolation), IQR = 4.5
+        let (lower, upper) = OutlierMethod::Iqr { k: 1.5 }.fences(&VALUES);
+        assert_close(lower, -3.5, 1e-12);
+        assert_close(upper, 14.5, 1e-12);
+
+        // Median 5.5, MAD 2.5 · 1.4826 = 3.7065
+        let (lower, upper) = OutlierMethod::Mad { threshold: 3.5 }.fences(&VALUES);
+        assert_close(lower, 5.5 - 3.5 * 3.7065, 1e-12);
+        assert_close(upper, 5.5 + 3.5 * 3.7065, 1e-12);
+
+        let (lower, upper) = OutlierMethod::Percentile { lower: 10.0, upper: 90.0 }.fences(&VALUES);
+        assert_close(lower, 1.9, 1e-12);
+        assert_close(upper, 18.1, 1e-12);
+    }
+
+    #[test]
+    fn missing_values_do_not_move_fences() {
+        let mut df = df!(Col::Salary.header() => [Some(1.0), Some(2.0), None, Some(3.0), None, Some(4.0), Some(5.0)]).unwrap();
+        let summary = apply_rule(&mut df, &rule("percentile:0-100:flag")).unwrap();
+        assert_eq!((summary.lower, summary.upper, summary.affected), (1.0, 5.0, 0));
+        let flags = df.column("salary_outlier").unwrap().bool().unwrap();
+        assert_eq!(flags.get(2), Some(false));
+    }
+
+    #[test]
+    fn zero_mad_flags_nothing() {
+        let fences = OutlierMethod::Mad { threshold: 3.5 }.fences(&[1.0, 1.0, 1.0, 1.0, 5.0]);
+        assert_eq!(fences, (f64::NEG_INFINITY, f64::INFINITY));
+    }
+
+    #[test]
+    fn drop_winsorize_and_flag() {
+        let mut df = frame();
+        let summary = apply_rule(&mut df, &rule("iqr:drop")).unwrap();
+        assert_eq!((summary.affected, df.height()), (1, 9));
+
+        let mut df = frame();
+        apply_rule(&mut df, &rule("iqr:winsorize")).unwrap();
+        let capped = df.column(Col::Salary.header()).unwrap().f64().unwrap().get(9);
+        assert_eq!(capped, Some(14.5));
+
+        let mut df = frame();
+        apply_rule(&mut df, &rule("iqr:flag")).unwrap();
+        let flags = df.column("salary_outlier").unwrap().bool().unwrap();
+        assert_eq!(flags.into_iter().filter(|f| *f == Some(true)).count(), 1);
+        assert_eq!(flags.get(9), Some(true));
+        assert_eq!(df.column(Col::Salary.header()).unwrap().f64().unwrap().get(9), Some(100.0));
+    }
+
+    #[test]
+    fn rules_use_fences_of_their_own_column() {
+        let mut df = frame();
+        let rules = [
+            rule("iqr:flag"),
+            OutlierRule::parse(Col::Bonus.key(), "percentile:10-90:winsorize").unwrap(),
+        ];
+        let summaries = apply_rules(&mut df, &rules).unwrap();
+        assert_eq!((summaries[0].lower, summaries[0].upper), (-3.5, 14.5));
+        // Bonus 0 bis 90: 10. und 90. Perzentil sind 9 und 81
+        assert_close(summaries[1].lower, 9.0, 1e-12);
+        assert_close(summaries[1].upper, 81.0, 1e-12);
+        assert_eq!(summaries[1].affected, 2);
+        let bonus = df.column(Col::Bonus.header()).unwrap().f64().unwrap();
+        assert_close(bonus.get(0).unwrap(), 9.0, 1e-12);
+        assert_close(bonus.get(9).unwrap(), 81.0, 1e-12);
+    }
+
+    #[test]
+    fn parses_short_form_and_json() {
+        assert_eq!(rule("iqr").method, OutlierMethod::Iqr { k: DEFAULT_IQR_K });
+        assert_eq!(rule("iqr").action, OutlierAction::Winsorize);
+        let mad = rule("mad:3:drop");
+        assert_eq!((mad.method, mad.action), (OutlierMethod::Mad { threshold: 3.0 }, OutlierAction::Drop));
+        assert_eq!(rule("percentile:1-99").method, OutlierMethod::Percentile { lower: 1.0, upper: 99.0 });
+        for invalid in ["iqr:abc", "percentile:99-1", "mad:0", "zscore", "iqr:1:2:flag"] {
+            assert!(OutlierRule::parse("salary", invalid).is_err(), "{invalid}");
+        }
+
+        let config: OutlierConfig = serde_json::from_str(
+            r#"{"rules": [
+                {"column": "salary", "method": "iqr", "action": "flag"},
+                {"column": "bonus", "method": "percentile", "lower": 5, "upper": 95, "action": "winsorize"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(config.rules[0].method, OutlierMethod::Iqr { k: DEFAULT_IQR_K });
+        assert_eq!(config.rules[1].method, OutlierMethod::Percentile { lower: 5.0, upper: 95.0 });
+        assert_eq!(config.rules[1].action, OutlierAction::Winsorize);
+    }
+}
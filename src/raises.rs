@@ -0,0 +1,274 @@
+// src/raises.rs
+
+use crate::columns::{registry, Col};
+use crate::data_analysis::{ensure_rows, grouped_values, median, numeric_column, quantile};
+use crate::error::{AppError, AppResult};
+use polars::prelude::*;
+use rust_web_app::binning::BinStrategy;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+
+/// Standard-Aufschlüsselungen für `/raises/data`. Nach einem Jobwechsel fragt die Umfrage
+/// nicht; `lost_job_covid` zeigt die Antworten auf die Frage nach dem Jobverlust durch Corona.
+pub const DEFAULT_RAISE_BREAKDOWNS: [&str; 3] = ["seniority", "company_type", "lost_job_covid"];
+
+/// Erhöhungen über diesem Wert (in %) gelten als unplausibel
+pub const DEFAULT_MAX_INCREASE_PCT: f64 = 100.0;
+
+/// Senkungen über diesem Wert (in %) gelten als unplausibel
+pub const DEFAULT_MAX_DECREASE_PCT: f64 = 50.0;
+
+/// Kleinere Vorjahresgehälter sind vermutlich Monatsgehälter oder Tippfehler
+pub const MIN_PLAUSIBLE_LAST_SALARY: f64 = 10_000.0;
+
+/// Höchstzahl der auffälligen Zeilen in der Antwort
+pub const MAX_LISTED_FLAGS: usize = 100;
+
+/// Verhältnis der Gehälter, das auf eine Verwechslung von Monats- und Jahresgehalt hindeutet
+const MONTHLY_RATIO: std::ops::RangeInclusive<f64> = 10.0..=14.0;
+
+/// Optionen für `raise_analysis`
+#[derive(Debug, Clone)]
+pub struct RaiseRequest {
+    /// Gruppenspalten (Schlüssel, Alias oder Spaltenname)
+    pub by: Vec<String>,
+    pub min_size: usize,
+    pub max_increase_pct: f64,
+    pub max_decrease_pct: f64,
+    /// Auffällige Änderungen mitrechnen statt ausschließen
+    pub include_flagged: bool,
+    /// Klassen des Histogramms der prozentualen Änderung
+    pub strategy: BinStrategy,
+}
+
+/// Grund, warum eine Änderung unplausibel ist
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RaiseFlag {
+    LastYearTooLow,
+    MonthlySalary,
+    Increase,
+    Decrease,
+}
+
+impl RaiseFlag {
+    fn label(&self) -> &'static str {
+        match self {
+            RaiseFlag::LastYearTooLow => "last_year_too_low",
+            RaiseFlag::MonthlySalary => "monthly_salary",
+            RaiseFlag::Increase => "implausible_increase",
+            RaiseFlag::Decrease => "implausible_decrease",
+        }
+    }
+}
+
+/// Gehaltsänderung eines Befragten
+struct Raise {
+    row: usize,
+    salary: f64,
+    last_year: f64,
+    absolute: f64,
+    percent: Option<f64>,
+    bonus_change: Option<f64>,
+    flags: Vec<RaiseFlag>,
+}
+
+impl Raise {
+    fn new(row: usize, salary: f64, last_year: f64, bonus_change: Option<f64>, request: &RaiseRequest) -> Raise {
+        let absolute = salary - last_year;
+        let percent = (last_year > 0.0).then(|| absolute / last_year * 100.0);
+
+        let mut flags = Vec::new();
+        if last_year < MIN_PLAUSIBLE_LAST_SALARY {
+            flags.push(RaiseFlag::LastYearTooLow);
+        }
+        if last_year > 0.0 && (MONTHLY_RATIO.contains(&(salary / last_year)) || MONTHLY_RATIO.contains(&(last_year / salary))) {
+            flags.push(RaiseFlag::MonthlySalary);
+        }
+        match percent {
+            Some(p) if p > request.max_increase_pct => flags.push(RaiseFlag::Increase),
+            Some(p) if p < -request.max_decrease_pct => flags.push(RaiseFlag::Decrease),
+            _ => {}
+        }
+        Raise { row, salary, last_year, absolute, percent, bonus_change, flags }
+    }
+}
+
+/// Kennzahlen einer Liste von Änderungen
+fn describe(values: &[f64]) -> serde_json::Value {
+    if values.is_empty() {
+        return json!({ "count": 0 });
+    }
+    let n = values.len() as f64;
+    let share = |keep: fn(&f64) -> bool| values.iter().filter(|v| keep(v)).count() as f64 / n;
+    json!({
+        "count": values.len(),
+        "mean": values.iter().sum::<f64>() / n,
+        "median": median(values),
+        "p25": quantile(values, 0.25),
+        "p75": quantile(values, 0.75),
+        "share_increased": share(|v| *v > 0.0),
+        "share_unchanged": share(|v| *v == 0.0),
+        "share_decreased": share(|v| *v < 0.0),
+    })
+}
+
+/// Gehaltsänderung gegenüber dem Vorjahr (absolut und in %) je Befragtem, ihre Verteilung
+/// und Aufschlüsselungen nach `request.by`. Unplausible Änderungen werden markiert und
+/// ohne `include_flagged` nicht mitgerechnet.
+pub fn raise_analysis(df: &DataFrame, request: &RaiseRequest) -> AppResult<serde_json::Value> {
+    ensure_rows(df)?;
+    let salary = numeric_column(df, Col::Salary)?;
+    let last_year = numeric_column(df, Col::SalaryLastYear)?;
+    // Boni des Vorjahres gibt es nicht in jedem Jahrgang
+    let bonus = numeric_column(df, Col::Bonus).ok();
+    let bonus_last_year = numeric_column(df, Col::BonusLastYear).ok();
+
+    let raises = (0..df.height())
+        .filter_map(|row| {
+            let (salary, last) = (salary.get(row)?, last_year.get(row)?);
+            let bonus_change = match (&bonus, &bonus_last_year) {
+                (Some(bonus), Some(last)) => bonus.get(row).zip(last.get(row)).map(|(b, l)| b - l),
+                _ => None,
+            };
+            Some(Raise::new(row, salary, last, bonus_change, request))
+        })
+        .collect::<Vec<Raise>>();
+    if raises.is_empty() {
+        return Err(AppError::EmptyDataset("keine Antworten mit Gehalt des Vorjahres".to_string()));
+    }
+
+    let mut flag_counts = BTreeMap::<&str, usize>::new();
+    for flag in raises.iter().flat_map(|raise| &raise.flags) {
+        *flag_counts.entry(flag.label()).or_insert(0) += 1;
+    }
+    let included = raises
+        .iter()
+        .filter(|raise| request.include_flagged || raise.flags.is_empty())
+        .collect::<Vec<&Raise>>();
+
+    let percent = included.iter().filter_map(|raise| raise.percent).collect::<Vec<f64>>();
+    let absolute = included.iter().map(|raise| raise.absolute).collect::<Vec<f64>>();
+    let bonus_changes = included.iter().filter_map(|raise| raise.bonus_change).collect::<Vec<f64>>();
+    let histogram = request.strategy.histogram(&percent).map_err(AppError::InvalidParameter)?;
+
+    let breakdowns = request
+        .by
+        .iter()
+        .map(|name| Ok((name.clone(), breakdown(df, &included, name, request.min_size)?)))
+        .collect::<AppResult<serde_json::Map<String, serde_json::Value>>>()?;
+
+    let flagged = raises
+        .iter()
+        .filter(|raise| !raise.flags.is_empty())
+        .take(MAX_LISTED_FLAGS)
+        .map(|raise| {
+            json!({
+                "row": raise.row,
+                "salary": raise.salary,
+                "salary_last_year": raise.last_year,
+                "raise_abs": raise.absolute,
+                "raise_pct": raise.percent,
+                "flags": raise.flags.iter().map(RaiseFlag::label).collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(json!({
+        "respondents": raises.len(),
+        "included": included.len(),
+        "thresholds": {
+            "max_increase_pct": request.max_increase_pct,
+            "max_decrease_pct": request.max_decrease_pct,
+            "min_last_year": MIN_PLAUSIBLE_LAST_SALARY,
+            "include_flagged": request.include_flagged,
+        },
+        "raise_pct": describe(&percent),
+        "raise_abs": describe(&absolute),
+        "bonus_change": describe(&bonus_changes),
+        "histogram": histogram,
+        "breakdowns": breakdowns,
+        "flag_counts": flag_counts,
+        "flagged_count": raises.iter().filter(|raise| !raise.flags.is_empty()).count(),
+        "flagged": flagged,
+    }))
+}
+
+/// Änderungen je Wert einer Gruppenspalte; fehlende Werte und kleine Gruppen landen in "other"
+fn breakdown(df: &DataFrame, raises: &[&Raise], name: &str, min_size: usize) -> AppResult<serde_json::Value> {
+    let column = registry().resolve_name(df, name)?;
+    let values = df.column(&column)?;
+    if values.dtype().is_nested() {
+        return Err(AppError::wrong_type(&column, "einzelne Werte", values.dtype()));
+    }
+    let values = values.cast(&DataType::String)?;
+    let values = values.str()?;
+    let groups = raises.iter().map(|raise| values.get(raise.row)).collect::<StringChunked>();
+
+    let frame = DataFrame::new(vec![
+        groups.with_name("group".into()).into_series().into(),
+        Series::new("raise_pct".into(), raises.iter().map(|raise| raise.percent).collect::<Vec<_>>()).into(),
+        Series::new("raise_abs".into(), raises.iter().map(|raise| raise.absolute).collect::<Vec<_>>()).into(),
+    ])?;
+    let absolute = grouped_values(&frame, "group", "raise_abs", min_size)?
+        .into_iter()
+        .collect::<HashMap<String, Vec<f64>>>();
+
+    let groups = grouped_values(&frame, "group", "raise_pct", min_size)?
+        .into_iter()
+        .map(|(group, percent)| {
+            let n = percent.len() as f64;
+            json!({
+                "name": group,
+                "count": percent.len(),
+                "median_pct": median(&percent),
+                "mean_pct": percent.iter().sum::<f64>() / n,
+                "median_abs": absolute.get(&group).map(|values| median(values)),
+                "share_increased": percent.iter().filter(|v| **v > 0.0).count() as f64 / n,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(json!({ "column": column, "groups": groups }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> RaiseRequest {
+        RaiseRequest {
+            by: Vec::new(),
+            min_size: 1,
+            max_increase_pct: DEFAULT_MAX_INCREASE_PCT,
+            max_decrease_pct: DEFAULT_MAX_DECREASE_PCT,
+            include_flagged: false,
+            strategy: BinStrategy::default(),
+        }
+    }
+
+    #[test]
+    fn flags_implausible_changes() {
+        let request = request();
+        assert!(Raise::new(0, 66_000.0, 60_000.0, None, &request).flags.is_empty());
+        assert_eq!(
+            Raise::new(0, 60_000.0, 5_000.0, None, &request).flags,
+            vec![RaiseFlag::LastYearTooLow, RaiseFlag::MonthlySalary, RaiseFlag::Increase]
+        );
+        assert_eq!(Raise::new(0, 20_000.0, 60_000.0, None, &request).flags, vec![RaiseFlag::Decrease]);
+    }
+
+    #[test]
+    fn missing_bonus_keeps_respondent() {
+        let df = df!(
+            Col::Salary.header() => [66_000.0, 55_000.0],
+            Col::SalaryLastYear.header() => [60_000.0, 50_000.0],
+            Col::Bonus.header() => [Some(6_000.0), None],
+            Col::BonusLastYear.header() => [Some(5_000.0), None],
+        )
+        .unwrap();
+        let result = raise_analysis(&df, &request()).unwrap();
+        assert_eq!(result["respondents"], 2);
+        assert_eq!(result["raise_pct"]["count"], 2);
+        assert_eq!(result["bonus_change"]["count"], 1);
+    }
+}
@@ -0,0 +1,287 @@
+// src/reload.rs
+
+use crate::cache::{cache_path, load_fresh, write_cache, CacheKey, CachedSurvey};
+use crate::data_analysis::{load_data, prepare_survey};
+use crate::datasets::{unix_now, unprepared_dataset_name, Dataset, DatasetKind, DatasetRegistry, DEFAULT_DATASET};
+use crate::imputation::ImputationPolicy;
+use crate::outliers::OutlierRule;
+use crate::storage::{import_surveys, is_database_file};
+use crate::survey_years::{merge_years, CURRENT_YEAR};
+use crate::validation::ValidationReport;
+use crate::web_app::{lock_state, AppState};
+use polars::prelude::*;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+pub type LoadError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Quelldateien je Jahrgang und alles, was ihre Aufbereitung bestimmt
+#[derive(Debug, Clone)]
+pub struct SurveySources {
+    pub files: Vec<(i32, String)>,
+    /// CSV-Dateien, die vor jedem Laden in die Datenbank aus `files` importiert werden
+    /// (leer ohne Datenbank)
+    pub imports: Vec<(i32, String)>,
+    /// Schlüssel über die Konfiguration (ohne Quelldateien)
+    pub config_key: CacheKey,
+    pub imputation: Vec<ImputationPolicy>,
+    pub outliers: Vec<OutlierRule>,
+}
+
+/// Ein geladener Jahrgang
+pub struct LoadedYear {
+    pub year: i32,
+    pub source: String,
+    /// Daten vor `prepare_survey`; fehlen, wenn nur eine weitergegebene Cache-Datei vorliegt
+    pub raw: Option<DataFrame>,
+    pub report: ValidationReport,
+    pub cleaning_steps: Vec<String>,
+}
+
+/// Ergebnis eines vollständigen Ladevorgangs
+pub struct LoadedSurveys {
+    pub df: DataFrame,
+    pub years: Vec<LoadedYear>,
+    /// Schlüssel über Konfiguration und alle geladenen Dateien
+    pub data_hash: String,
+}
+
+impl LoadedSurveys {
+    /// `prepared` und `unprepared-<jahr>` im Register ersetzen; Uploads bleiben erhalten
+    pub fn register(self, registry: &mut DatasetRegistry) {
+        registry.retain(|kind| !matches!(kind, DatasetKind::Prepared | DatasetKind::Unprepared));
+        let mut sources = Vec::new();
+        let mut steps: Vec<String> = Vec::new();
+        let mut reports = Vec::new();
+        for year in self.years {
+            sources.push(year.source.clone());
+            for step in &year.cleaning_steps {
+                if !steps.contains(step) {
+                    steps.push(step.clone());
+                }
+            }
+            if let Some(raw) = year.raw {
+                let name = unprepared_dataset_name(year.year);
+                registry.insert(Dataset::new(&name, DatasetKind::Unprepared, raw, vec![year.source], Vec::new(), vec![year.report.clone()]));
+            }
+            reports.push(year.report);
+        }
+        registry.insert(Dataset::new(DEFAULT_DATASET, DatasetKind::Prepared, self.df, sources, steps, reports));
+    }
+}
+
+/// Änderungszeit und Größe einer Datei (None, wenn sie fehlt)
+type Fingerprint = Vec<Option<(SystemTime, u64)>>;
+
+impl SurveySources {
+    /// Alle vorhandenen Jahrgänge laden, bereinigen, ergänzen und zusammenführen.
+    /// Aktuelle Caches werden verwendet, sonst neu geschrieben.
+    pub fn load(&self) -> Result<LoadedSurveys, LoadError> {
+        let mut frames = Vec::new();
+        let mut years = Vec::new();
+        let mut data_key = self.config_key.clone();
+
+        // Neue oder geänderte CSV-Dateien zuerst in die Datenbank übernehmen
+        if let Some((_, database)) = self.files.iter().find(|(_, path)| is_database_file(path)) {
+            import_surveys(database, &self.imports)?;
+        }
+
+        for (year, path) in &self.files {
+            let (year, path) = (*year, path.as_str());
+            let cached_path = cache_path(path);
+            if !Path::new(path).exists() {
+                // Weitergegebene Cache-Datei ohne CSV
+                if cached_path.exists() {
+                    let cached_path = cached_path.display().to_string();
+                    println!("Lade aufbereitete Daten aus {cached_path} ({year})...");
+                    let (year_df, report) = load_data(&cached_path, year)?;
+                    data_key.add_file(&cached_path)?;
+                    frames.push(year_df);
+                    years.push(LoadedYear {
+                        year,
+                        source: cached_path,
+                        raw: None,
+                        report,
+                        cleaning_steps: Vec::new(),
+                    });
+                    continue;
+                }
+                if year != CURRENT_YEAR {
+                    continue;
+                }
+            }
+
+            let mut key = self.config_key.clone();
+            key.add_str(&year.to_string()).add_file(path)?;
+            data_key.add_str(&key.hex());
+            // Die Datenbank enthält alle Jahrgänge, ein Cache je Jahr hätte denselben Pfad
+            let cacheable = !is_database_file(path);
+            let fresh = if cacheable { load_fresh(path, &key) } else { None };
+            let survey = match fresh {
+                Some(survey) => {
+                    println!("Cache {} ist aktuell. Shape: {:?}", cached_path.display(), survey.df.shape());
+                    survey
+                }
+                None => {
+                    println!("Lade Daten aus {path} ({year})...");
+                    let (raw, report) = load_data(path, year)?;
+                    println!("Daten geladen. Shape: {:?}", raw.shape());
+                    report.print_summary();
+
+                    let mut df = raw.clone();
+                    let cleaning_steps = prepare_survey(&mut df, &self.imputation, &self.outliers)?;
+                    let mut survey = CachedSurvey { df, raw, report, cleaning_steps };
+
+                    // Ein nicht schreibbarer Cache verlangsamt nur den nächsten Start
+                    if cacheable {
+                        match write_cache(path, &key, &mut survey) {
+                            Ok(cached_path) => println!("Cache geschrieben: {}", cached_path.display()),
+                            Err(e) => println!("Cache konnte nicht geschrieben werden: {e}"),
+                        }
+                    }
+                    survey
+                }
+            };
+
+            let CachedSurvey { df, raw, report, cleaning_steps } = survey;
+            frames.push(df);
+            years.push(LoadedYear {
+                year,
+                source: path.to_string(),
+                raw: Some(raw),
+                report,
+                cleaning_steps,
+            });
+        }
+
+        let df = merge_years(frames)?;
+        if df.height() == 0 {
+            return Err("Nach der Bereinigung sind keine Zeilen übrig".into());
+        }
+        println!("Alle Jahrgänge zusammengeführt. Shape: {:?}", df.shape());
+        Ok(LoadedSurveys {
+            df,
+            years,
+            data_hash: data_key.hex(),
+        })
+    }
+
+    /// Überwachte Dateien: Quellen und die in die Datenbank importierten CSV-Dateien
+    fn watched(&self) -> impl Iterator<Item = &String> {
+        self.files.iter().chain(&self.imports).map(|(_, path)| path)
+    }
+
+    /// Zustand der Quelldateien (und ihrer Caches, falls die CSV fehlt)
+    fn fingerprint(&self) -> Fingerprint {
+        self.watched()
+            .map(|path| {
+                let path = Path::new(path);
+                let metadata = std::fs::metadata(path)
+                    .or_else(|_| std::fs::metadata(cache_path(&path.display().to_string())))
+                    .ok()?;
+                Some((metadata.modified().ok()?, metadata.len()))
+            })
+            .collect()
+    }
+}
+
+/// Ausgang des letzten Ladevorgangs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReloadState {
+    /// Daten entsprechen dem letzten erfolgreichen Ladevorgang
+    Loaded,
+    /// Letzter Versuch fehlgeschlagen, die vorherigen Daten bleiben aktiv
+    Failed,
+}
+
+/// Stand des automatischen Neuladens für `/reload-status`
+#[derive(Debug, Clone, Serialize)]
+pub struct ReloadStatus {
+    pub state: ReloadState,
+    /// Schlüssel der aktiven Daten (Konfiguration + Quelldateien)
+    pub data_hash: String,
+    /// Zeitpunkt, zu dem die aktiven Daten geladen wurden (Unix-Sekunden)
+    pub loaded_at: u64,
+    /// Letzter Ladeversuch (Unix-Sekunden)
+    pub last_attempt: u64,
+    pub last_error: Option<String>,
+    /// Erfolgreiche Neuladevorgänge seit dem Start
+    pub reloads: usize,
+    pub sources: Vec<String>,
+}
+
+impl ReloadStatus {
+    pub fn new(sources: &SurveySources, data_hash: String) -> Self {
+        let now = unix_now();
+        ReloadStatus {
+            state: ReloadState::Loaded,
+            data_hash,
+            loaded_at: now,
+            last_attempt: now,
+            last_error: None,
+            reloads: 0,
+            sources: sources.watched().cloned().collect(),
+        }
+    }
+}
+
+/// Quelldateien regelmäßig prüfen und bei Änderungen neu laden.
+/// Geladen wird erst, wenn sich die Dateien zwei Prüfungen lang nicht mehr ändern,
+/// damit halb geschriebene Dateien nicht eingelesen werden (siehe `apply_reload`).
+pub fn spawn_watcher(state: Arc<Mutex<AppState>>, sources: Arc<SurveySources>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut loaded = sources.fingerprint();
+        let mut previous = loaded.clone();
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let current = sources.fingerprint();
+            let stable = current == previous;
+            previous = current.clone();
+            if !stable || current == loaded {
+                continue;
+            }
+            loaded = current;
+
+            println!("Quelldateien geändert, lade neu...");
+            let task_sources = sources.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                task_sources.load().map_err(|e| e.to_string())
+            })
+            .await
+            .unwrap_or_else(|e| Err(format!("Ladevorgang abgebrochen: {e}")));
+
+            apply_reload(&mut lock_state(&state), result);
+        }
+    });
+}
+
+/// Ergebnis eines Ladevorgangs übernehmen. Das neue DataFrame ersetzt das alte
+/// in einem Schritt; bei einem Fehler bleibt das alte aktiv.
+pub fn apply_reload(state: &mut AppState, result: Result<LoadedSurveys, String>) {
+    state.reload.last_attempt = unix_now();
+    match result {
+        Ok(surveys) if surveys.data_hash == state.reload.data_hash => {
+            state.reload.state = ReloadState::Loaded;
+            state.reload.last_error = None;
+        }
+        Ok(surveys) => {
+            state.reload.data_hash = surveys.data_hash.clone();
+            let shape = surveys.df.shape();
+            surveys.register(&mut state.datasets);
+            state.reload.state = ReloadState::Loaded;
+            state.reload.loaded_at = state.reload.last_attempt;
+            state.reload.last_error = None;
+            state.reload.reloads += 1;
+            println!("Daten neu geladen. Shape: {shape:?}");
+        }
+        Err(e) => {
+            println!("Neuladen fehlgeschlagen, alte Daten bleiben aktiv: {e}");
+            state.reload.state = ReloadState::Failed;
+            state.reload.last_error = Some(e);
+        }
+    }
+}
@@ -0,0 +1,521 @@
+// src/stats.rs
+
+use crate::bootstrap::{bootstrap_difference, bootstrap_statistic, BootstrapConfig, Statistic};
+use crate::columns::registry;
+use crate::compensation::resolve_metric;
+use crate::data_analysis::{median, numeric_series, quantile, text_series};
+use crate::error::{AppError, AppResult};
+use polars::prelude::*;
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Quantile, wenn die Anfrage keine angibt
+pub const DEFAULT_QUANTILES: [f64; 5] = [0.05, 0.25, 0.5, 0.75, 0.95];
+
+/// Anzahl häufigster Werte im Profil einer Textspalte
+pub const DEFAULT_TOP_K: usize = 10;
+
+/// Quantile aus einer Liste wie `0.1,0.9` oder `p10,p90` lesen
+pub fn parse_quantiles(spec: &str) -> Result<Vec<f64>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            let q = match token.strip_prefix('p') {
+                Some(percent) => percent.parse::<f64>().map(|p| p / 100.0),
+                None => token.parse::<f64>(),
+            }
+            .map_err(|_| format!("'{token}' ist kein Quantil (z. B. 0.25 oder p25)"))?;
+            if !(0.0..=1.0).contains(&q) {
+                return Err(format!("Quantil '{token}' liegt nicht zwischen 0 und 1"));
+            }
+            Ok(q)
+        })
+        .collect()
+}
+
+/// Schlüssel eines Quantils in der Antwort, z. B. `p25` oder `p2.5`
+pub fn quantile_label(q: f64) -> String {
+    format!("p{}", (q * 100.0 * 1e6).round() / 1e6)
+}
+
+/// Mittelwert, Stichproben-Standardabweichung (n - 1), Schiefe und Exzess-Kurtosis.
+/// Schiefe und Kurtosis sind wie in pandas für kleine Stichproben korrigiert.
+fn moments(values: &[f64]) -> (f64, f64, f64, f64) {
+    let n = values.len() as f64;
+    if values.is_empty() {
+        return (f64::NAN, f64::NAN, f64::NAN, f64::NAN);
+    }
+    let mean = values.iter().sum::<f64>() / n;
+    let (mut m2, mut m3, mut m4) = (0.0, 0.0, 0.0);
+    for &x in values {
+        let d = x - mean;
+        m2 += d * d;
+        m3 += d * d * d;
+        m4 += d * d * d * d;
+    }
+    let std = if n > 1.0 { (m2 / (n - 1.0)).sqrt() } else { f64::NAN };
+    let (m2, m3, m4) = (m2 / n, m3 / n, m4 / n);
+    if m2 == 0.0 {
+        return (mean, std, f64::NAN, f64::NAN);
+    }
+    let skew = if n > 2.0 {
+        m3 / m2.powf(1.5) * (n * (n - 1.0)).sqrt() / (n - 2.0)
+    } else {
+        f64::NAN
+    };
+    let kurtosis = if n > 3.0 {
+        let g2 = m4 / (m2 * m2) - 3.0;
+        ((n + 1.0) * g2 + 6.0) * (n - 1.0) / ((n - 2.0) * (n - 3.0))
+    } else {
+        f64::NAN
+    };
+    (mean, std, skew, kurtosis)
+}
+
+/// Kennzahlen einer numerischen Spalte
+fn numeric_profile(df: &DataFrame, name: &str, quantiles: &[f64]) -> AppResult<serde_json::Value> {
+    let series = numeric_series(df, name)?;
+    let values = series.into_iter().flatten().collect::<Vec<f64>>();
+    let (mean, std, skew, kurtosis) = moments(&values);
+    let quantiles = quantiles
+        .iter()
+        .map(|&q| (quantile_label(q), json!(quantile(&values, q))))
+        .collect::<serde_json::Map<String, serde_json::Value>>();
+    Ok(json!({
+        "column": name,
+        "kind": "numeric",
+        "count": values.len(),
+        "null_count": series.null_count(),
+        "mean": mean,
+        "median": median(&values),
+        "std": std,
+        "min": values.iter().copied().reduce(f64::min),
+        "max": values.iter().copied().reduce(f64::max),
+        "skewness": skew,
+        "kurtosis": kurtosis,
+        "quantiles": quantiles,
+    }))
+}
+
+/// Anzahl verschiedener Werte und die `top_k` häufigsten einer Textspalte
+fn categorical_profile(df: &DataFrame, name: &str, top_k: usize) -> AppResult<serde_json::Value> {
+    let series = text_series(df, name)?;
+    let mut counts = HashMap::<&str, usize>::new();
+    for value in series.into_iter().flatten() {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    let count = series.len() - series.null_count();
+    let mut top = counts.iter().map(|(value, n)| (*value, *n)).collect::<Vec<_>>();
+    top.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let top = top
+        .into_iter()
+        .take(top_k)
+        .map(|(value, n)| json!({ "value": value, "count": n, "share": n as f64 / count as f64 }))
+        .collect::<Vec<_>>();
+    Ok(json!({
+        "column": name,
+        "kind": "categorical",
+        "count": count,
+        "null_count": series.null_count(),
+        "cardinality": counts.len(),
+        "top": top,
+    }))
+}
+
+/// Profil einer Spalte (Schlüssel, Alias oder Spaltenname): Kennzahlen für
+/// numerische Spalten, häufigste Werte für Textspalten, sonst ein Fehler
+pub fn column_profile(df: &DataFrame, column: &str, quantiles: &[f64], top_k: usize) -> AppResult<serde_json::Value> {
+    let name = resolve_metric(df, column)?;
+    let dtype = df.column(&name)?.dtype();
+    if dtype.is_numeric() {
+        numeric_profile(df, &name, quantiles)
+    } else if dtype == &DataType::String {
+        categorical_profile(df, &name, top_k)
+    } else {
+        Err(AppError::wrong_type(&name, "numerisch oder Text", dtype))
+    }
+}
+
+/// Kennzahl je Gruppe in `/group-stats`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aggregation {
+    Count,
+    Mean,
+    Median,
+    Std,
+    Min,
+    Max,
+    Sum,
+    /// `p25` usw.
+    Quantile(f64),
+}
+
+impl Aggregation {
+    /// Kurzform wie `median` oder `p90`
+    pub fn parse(token: &str) -> Result<Aggregation, String> {
+        Ok(match token.trim().to_lowercase().as_str() {
+            "count" | "n" => Aggregation::Count,
+            "mean" | "avg" => Aggregation::Mean,
+            "median" => Aggregation::Median,
+            "std" => Aggregation::Std,
+            "min" => Aggregation::Min,
+            "max" => Aggregation::Max,
+            "sum" => Aggregation::Sum,
+            other if other.starts_with('p') => match parse_quantiles(other)?.as_slice() {
+                [q] => Aggregation::Quantile(*q),
+                _ => return Err(format!("'{token}' ist keine Kennzahl")),
+            },
+            _ => {
+                return Err(format!(
+                    "'{token}' ist keine Kennzahl (count, mean, median, std, min, max, sum, p<0-100>)"
+                ))
+            }
+        })
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Aggregation::Count => "count".to_string(),
+            Aggregation::Mean => "mean".to_string(),
+            Aggregation::Median => "median".to_string(),
+            Aggregation::Std => "std".to_string(),
+            Aggregation::Min => "min".to_string(),
+            Aggregation::Max => "max".to_string(),
+            Aggregation::Sum => "sum".to_string(),
+            Aggregation::Quantile(q) => quantile_label(*q),
+        }
+    }
+
+    /// Kennzahl, für die sich ein Bootstrap-Intervall schätzen lässt
+    fn statistic(&self) -> Option<Statistic> {
+        match self {
+            Aggregation::Mean => Some(Statistic::Mean),
+            Aggregation::Median => Some(Statistic::Median),
+            Aggregation::Quantile(q) => Some(Statistic::Quantile(*q)),
+            _ => None,
+        }
+    }
+
+    fn expr(&self, metric: &str) -> Expr {
+        let value = col(metric);
+        let expr = match self {
+            Aggregation::Count => value.count(),
+            Aggregation::Mean => value.mean(),
+            Aggregation::Median => value.median(),
+            Aggregation::Std => value.std(1),
+            Aggregation::Min => value.min(),
+            Aggregation::Max => value.max(),
+            Aggregation::Sum => value.sum(),
+            Aggregation::Quantile(q) => value.quantile(lit(*q), QuantileMethod::Linear),
+        };
+        expr.alias(self.label())
+    }
+}
+
+/// Anfrage an `group_stats`
+#[derive(Debug, Clone)]
+pub struct GroupStatsRequest {
+    /// Gruppenspalten (Schlüssel, Alias oder Spaltenname)
+    pub by: Vec<String>,
+    pub metric: String,
+    pub aggregations: Vec<Aggregation>,
+    /// Kennzahl oder Gruppenspalte; ohne Angabe nach Anzahl
+    pub sort: Option<String>,
+    /// Ohne Angabe absteigend für Kennzahlen, aufsteigend für Gruppenspalten
+    pub descending: Option<bool>,
+    pub min_size: usize,
+    pub top: Option<usize>,
+    /// Konfidenzintervalle für Mittelwert, Median und Quantile sowie Differenzen zur größten Gruppe
+    pub bootstrap: Option<BootstrapConfig>,
+}
+
+/// Werte der Kennzahl je Gruppenschlüssel (Gruppenspalten als Text) für den Bootstrap
+fn group_samples(df: &DataFrame, by: &[String], metric: &str) -> AppResult<HashMap<Vec<Option<String>>, Vec<f64>>> {
+    let keys = by
+        .iter()
+        .map(|name| df.column(name)?.cast(&DataType::String))
+        .collect::<PolarsResult<Vec<Column>>>()?;
+    let keys = keys.iter().map(|c| c.str()).collect::<PolarsResult<Vec<&StringChunked>>>()?;
+    let values = numeric_series(df, metric)?;
+    let mut samples = HashMap::<Vec<Option<String>>, Vec<f64>>::new();
+    for (i, value) in values.into_iter().enumerate() {
+        if let Some(value) = value {
+            let key = keys.iter().map(|k| k.get(i).map(str::to_string)).collect();
+            samples.entry(key).or_default().push(value);
+        }
+    }
+    Ok(samples)
+}
+
+/// Kennzahlen einer numerischen Spalte je Kombination der Gruppenspalten.
+/// Jede Gruppe enthält ihre Schlüssel, eine Beschriftung und `count` (Anzahl Werte),
+/// mit Bootstrap außerdem `ci` und `difference` (zur größten angezeigten Gruppe).
+pub fn group_stats(df: &DataFrame, request: &GroupStatsRequest) -> AppResult<serde_json::Value> {
+    if request.by.is_empty() {
+        return Err(AppError::InvalidParameter("mindestens eine Gruppenspalte ('by') ist nötig".to_string()));
+    }
+    let metric = resolve_metric(df, &request.metric)?;
+    let dtype = df.column(&metric)?.dtype();
+    if !dtype.is_numeric() {
+        return Err(AppError::wrong_type(&metric, "numerisch", dtype));
+    }
+    let by = request
+        .by
+        .iter()
+        .map(|name| {
+            let name = registry().resolve_name(df, name)?;
+            let dtype = df.column(&name)?.dtype();
+            if dtype.is_nested() {
+                return Err(AppError::wrong_type(&name, "einzelne Werte", dtype));
+            }
+            Ok(name)
+        })
+        .collect::<AppResult<Vec<String>>>()?;
+
+    let mut aggregations = vec![col(&metric).count().alias("count")];
+    aggregations.extend(
+        request
+            .aggregations
+            .iter()
+            .filter(|agg| **agg != Aggregation::Count)
+            .map(|agg| agg.expr(&metric)),
+    );
+    let labels = request.aggregations.iter().map(Aggregation::label).collect::<Vec<String>>();
+
+    let (sort_by, by_group) = match request.sort.as_deref() {
+        None => ("count".to_string(), false),
+        Some(sort) if sort == "count" || labels.iter().any(|l| l == sort) => (sort.to_string(), false),
+        Some(sort) => {
+            let name = registry().resolve_name(df, sort).ok().filter(|name| by.contains(name));
+            let name = name.ok_or_else(|| {
+                AppError::InvalidParameter(format!(
+                    "nach '{sort}' kann nicht sortiert werden, möglich: count, {}, {}",
+                    labels.iter().filter(|l| *l != "count").cloned().collect::<Vec<_>>().join(", "),
+                    by.join(", ")
+                ))
+            })?;
+            (name, true)
+        }
+    };
+    let descending = request.descending.unwrap_or(!by_group);
+
+    let grouped = df
+        .clone()
+        .lazy()
+        .with_column(col(&metric).cast(DataType::Float64))
+        .filter(col(&metric).is_not_null())
+        .group_by(by.iter().map(col).collect::<Vec<Expr>>())
+        .agg(aggregations)
+        .filter(col("count").gt_eq(lit(request.min_size as u32)))
+        .sort(
+            [sort_by.as_str()],
+            SortMultipleOptions::default()
+                .with_order_descending(descending)
+                .with_nulls_last(true),
+        )
+        .collect()?;
+
+    let total_groups = grouped.height();
+    let shown = grouped.head(request.top);
+    let counts = shown.column("count")?.cast(&DataType::UInt64)?;
+    let counts = counts.u64()?;
+    let shown_keys = by
+        .iter()
+        .map(|name| shown.column(name)?.cast(&DataType::String))
+        .collect::<PolarsResult<Vec<Column>>>()?;
+    let mut groups = Vec::with_capacity(shown.height());
+    let mut keys_by_group = Vec::with_capacity(shown.height());
+    for i in 0..shown.height() {
+        let mut keys = serde_json::Map::new();
+        let mut label = Vec::new();
+        for name in &by {
+            let value = shown.column(name)?.get(i)?;
+            let text = match &value {
+                AnyValue::Null => None,
+                AnyValue::String(s) => Some(s.to_string()),
+                other => Some(other.to_string()),
+            };
+            label.push(text.clone().unwrap_or_else(|| "(fehlt)".to_string()));
+            keys.insert(name.clone(), json!(text));
+        }
+        let mut group = json!({ "group": keys, "label": label.join(" / "), "count": counts.get(i) });
+        for agg in request.aggregations.iter().filter(|agg| **agg != Aggregation::Count) {
+            group[agg.label()] = json!(shown.column(&agg.label())?.f64()?.get(i));
+        }
+        groups.push(group);
+        keys_by_group.push(
+            shown_keys
+                .iter()
+                .map(|k| Ok(k.str()?.get(i).map(str::to_string)))
+                .collect::<PolarsResult<Vec<Option<String>>>>()?,
+        );
+    }
+
+    let mut reference = None;
+    if let Some(config) = &request.bootstrap {
+        let samples = group_samples(df, &by, &metric)?;
+        let statistics = request
+            .aggregations
+            .iter()
+            .filter_map(|agg| Some((agg.label(), agg.statistic()?)))
+            .collect::<Vec<_>>();
+        let largest = (0..groups.len()).max_by_key(|&i| (counts.get(i), std::cmp::Reverse(i)));
+        reference = largest.map(|i| groups[i]["label"].clone());
+        let reference_values = largest.and_then(|i| samples.get(&keys_by_group[i]));
+        for (i, group) in groups.iter_mut().enumerate() {
+            // Schlüssel, die sich als Text nicht wiederfinden, bekommen kein Intervall
+            let Some(values) = samples.get(&keys_by_group[i]) else {
+                continue;
+            };
+            let mut ci = serde_json::Map::new();
+            let mut difference = serde_json::Map::new();
+            for (label, statistic) in &statistics {
+                ci.insert(label.clone(), json!(bootstrap_statistic(values, *statistic, config).map(|i| i.describe())));
+                if let Some(reference_values) = reference_values.filter(|_| Some(i) != largest) {
+                    let interval = bootstrap_difference(values, reference_values, *statistic, config);
+                    difference.insert(label.clone(), json!(interval.map(|i| i.describe())));
+                }
+            }
+            group["ci"] = json!(ci);
+            if Some(i) != largest {
+                group["difference"] = json!(difference);
+            }
+        }
+    }
+
+    Ok(json!({
+        "by": by,
+        "metric": metric,
+        "aggregations": labels,
+        "min_size": request.min_size,
+        "total_groups": total_groups,
+        "reference": reference,
+        "bootstrap": request.bootstrap.as_ref().map(BootstrapConfig::describe),
+        "groups": groups,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::columns::Col;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "erwartet {expected}, erhalten {actual} (Toleranz {tolerance})"
+        );
+    }
+
+    #[test]
+    fn moments_match_pandas() {
+        // Referenz: pandas Series.mean(), std(), skew(), kurt()
+        let (mean, std, skew, kurtosis) = moments(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 100.0]);
+        assert_close(mean, 14.5, 1e-12);
+        assert_close(std, 30.152390728873666, 1e-9);
+        assert_close(skew, 3.118895745239087, 1e-9);
+        assert_close(kurtosis, 9.799244716163662, 1e-9);
+
+        let (mean, std, skew, kurtosis) = moments(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        assert_close(mean, 5.0, 1e-12);
+        assert_close(std, 2.138089935299395, 1e-9);
+        assert_close(skew, 0.8184875533567997, 1e-9);
+        assert_close(kurtosis, 0.940625, 1e-9);
+    }
+
+    #[test]
+    fn moments_of_short_or_constant_samples() {
+        assert!(moments(&[]).0.is_nan());
+        let (mean, std, skew, kurtosis) = moments(&[3.0, 5.0]);
+        assert_eq!(mean, 4.0);
+        assert_close(std, 2f64.sqrt(), 1e-12);
+        assert!(skew.is_nan() && kurtosis.is_nan());
+        let (_, std, skew, kurtosis) = moments(&[7.0; 5]);
+        assert_eq!(std, 0.0);
+        assert!(skew.is_nan() && kurtosis.is_nan());
+    }
+
+    #[test]
+    fn numeric_profile_reports_moments_of_present_values() {
+        let df = df!(Col::Salary.header() => [Some(2.0), Some(4.0), None, Some(4.0), Some(4.0), Some(5.0), Some(5.0), Some(7.0), Some(9.0)]).unwrap();
+        let profile = column_profile(&df, Col::Salary.key(), &[0.5], DEFAULT_TOP_K).unwrap();
+        assert_eq!((profile["count"].as_u64(), profile["null_count"].as_u64()), (Some(8), Some(1)));
+        assert_close(profile["skewness"].as_f64().unwrap(), 0.8184875533567997, 1e-9);
+        assert_close(profile["kurtosis"].as_f64().unwrap(), 0.940625, 1e-9);
+        assert_eq!(profile["quantiles"]["p50"].as_f64(), Some(4.5));
+    }
+
+    /// Senior: 3 Werte, Middle: 2, Junior: 1, ohne Angabe: 2
+    fn groups_frame() -> DataFrame {
+        df!(
+            Col::Seniority.header() => [Some("Senior"), Some("Senior"), Some("Senior"), Some("Middle"), Some("Middle"), Some("Junior"), None, None],
+            Col::Salary.header() => [80.0, 90.0, 100.0, 60.0, 70.0, 40.0, 65.0, 75.0],
+        )
+        .unwrap()
+    }
+
+    fn request(sort: Option<&str>, descending: Option<bool>, min_size: usize, top: Option<usize>) -> GroupStatsRequest {
+        GroupStatsRequest {
+            by: vec![Col::Seniority.key().to_string()],
+            metric: Col::Salary.key().to_string(),
+            aggregations: vec![Aggregation::Mean, Aggregation::Median],
+            sort: sort.map(str::to_string),
+            descending,
+            min_size,
+            top,
+            bootstrap: None,
+        }
+    }
+
+    fn labels(result: &serde_json::Value) -> Vec<&str> {
+        result["groups"].as_array().unwrap().iter().map(|g| g["label"].as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn groups_below_min_size_are_dropped() {
+        let result = group_stats(&groups_frame(), &request(None, None, 2, None)).unwrap();
+        assert_eq!(result["total_groups"], 3);
+        assert_eq!(labels(&result)[0], "Senior");
+        assert!(!labels(&result).contains(&"Junior"));
+        assert_eq!(result["groups"][0]["count"], 3);
+        assert_eq!(result["groups"][0]["mean"], 90.0);
+    }
+
+    #[test]
+    fn sort_by_metric_or_group_column_and_keep_top() {
+        let result = group_stats(&groups_frame(), &request(Some("mean"), None, 1, None)).unwrap();
+        assert_eq!(labels(&result), ["Senior", "(fehlt)", "Middle", "Junior"]);
+
+        let result = group_stats(&groups_frame(), &request(Some("mean"), Some(false), 1, Some(2))).unwrap();
+        assert_eq!(labels(&result), ["Junior", "Middle"]);
+        assert_eq!(result["total_groups"], 4);
+
+        // Gruppenspalten aufsteigend, fehlende Schlüssel zuletzt
+        let result = group_stats(&groups_frame(), &request(Some(Col::Seniority.key()), None, 1, None)).unwrap();
+        assert_eq!(labels(&result), ["Junior", "Middle", "Senior", "(fehlt)"]);
+    }
+
+    #[test]
+    fn unknown_sort_and_missing_by_are_rejected() {
+        let err = group_stats(&groups_frame(), &request(Some("p90"), None, 1, None)).unwrap_err();
+        assert!(matches!(err, AppError::InvalidParameter(_)));
+        let mut no_groups = request(None, None, 1, None);
+        no_groups.by.clear();
+        assert!(matches!(group_stats(&groups_frame(), &no_groups), Err(AppError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn bootstrap_covers_groups_with_missing_keys() {
+        let mut with_bootstrap = request(None, None, 2, None);
+        with_bootstrap.bootstrap = Some(BootstrapConfig { resamples: 200, ..BootstrapConfig::default() });
+        let result = group_stats(&groups_frame(), &with_bootstrap).unwrap();
+        assert_eq!(result["reference"], "Senior");
+        for group in result["groups"].as_array().unwrap() {
+            assert!(group["ci"]["mean"].is_object(), "kein Intervall für {}", group["label"]);
+        }
+        let missing = result["groups"].as_array().unwrap().iter().find(|g| g["label"] == "(fehlt)").unwrap();
+        assert!(missing["difference"]["median"].is_object());
+        assert!(result["groups"][0].get("difference").is_none());
+    }
+}
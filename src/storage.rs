@@ -0,0 +1,409 @@
+// src/storage.rs
+
+use crate::cache::CacheKey;
+use crate::columns::Col;
+use crate::data_analysis::{load_data, records_to_dataframe, SurveyRecord};
+use crate::survey_years::SURVEY_YEAR_COL;
+use crate::validation::ValidationReport;
+use polars::prelude::*;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Dateiendungen, die als SQLite-Datenbank gelten
+const DATABASE_EXTENSIONS: [&str; 2] = ["db", "sqlite"];
+
+/// Quelle in `responses` für Antworten aus `POST /responses`
+const SUBMISSION_SOURCE: &str = "responses";
+
+/// Speicherklasse einer Umfragespalte in SQLite
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SqlKind {
+    Text,
+    Integer,
+    Real,
+}
+
+fn sql_kind(col: Col) -> SqlKind {
+    match col {
+        Col::VacationDays => SqlKind::Integer,
+        Col::Age
+        | Col::Experience
+        | Col::ExperienceGermany
+        | Col::Salary
+        | Col::Bonus
+        | Col::SalaryLastYear
+        | Col::BonusLastYear
+        | Col::WfhSupport => SqlKind::Real,
+        _ => SqlKind::Text,
+    }
+}
+
+fn sql_err(e: rusqlite::Error) -> PolarsError {
+    polars_err!(ComputeError: "SQLite: {}", e)
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Aktueller Zeitpunkt im Format der Umfrage (`24/11/2020 11:14:15`, UTC)
+pub fn submission_timestamp() -> String {
+    let secs = unix_now();
+    let (days, time) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+    // Tage seit 1970-01-01 in ein Datum umrechnen (civil_from_days nach H. Hinnant)
+    let z = days + 719_468;
+    let (era, doe) = (z.div_euclid(146_097), z.rem_euclid(146_097));
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{day:02}/{month:02}/{year} {:02}:{:02}:{:02}",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Ist die Datei eine SQLite-Datenbank (und kein CSV)?
+pub fn is_database_file(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| DATABASE_EXTENSIONS.iter().any(|known| ext == *known))
+}
+
+/// Ergebnis eines CSV-Imports
+#[derive(Debug)]
+pub struct ImportOutcome {
+    pub source: String,
+    /// Eingefügte Zeilen (0, wenn die Datei unverändert war)
+    pub inserted: usize,
+    /// Ersetzte Zeilen eines früheren Imports derselben Datei
+    pub replaced: usize,
+    pub unchanged: bool,
+}
+
+impl ImportOutcome {
+    pub fn print_summary(&self) {
+        if self.unchanged {
+            println!("{} ist bereits importiert.", self.source);
+        } else {
+            println!(
+                "{} importiert: {} Zeilen eingefügt, {} ersetzt.",
+                self.source, self.inserted, self.replaced
+            );
+        }
+    }
+}
+
+/// Eingebettete SQLite-Datenbank mit allen Antworten
+pub struct SurveyDb {
+    conn: Connection,
+    path: String,
+}
+
+impl SurveyDb {
+    /// Datenbank öffnen und fehlende Tabellen anlegen: `responses` mit einer Spalte
+    /// je `Col::key` (geprüft, aber unbereinigt), `imports` mit Hash und Bericht je Quelldatei
+    pub fn open(path: &str) -> PolarsResult<SurveyDb> {
+        let conn = Connection::open(path).map_err(sql_err)?;
+        let columns = Col::ALL
+            .iter()
+            .map(|col| {
+                let kind = match sql_kind(*col) {
+                    SqlKind::Text => "TEXT",
+                    SqlKind::Integer => "INTEGER",
+                    SqlKind::Real => "REAL",
+                };
+                format!("{} {kind}", col.key())
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS responses (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                survey_year INTEGER NOT NULL,
+                source TEXT NOT NULL,
+                imported_at INTEGER NOT NULL,
+                {columns}
+            );
+            CREATE INDEX IF NOT EXISTS responses_year ON responses (survey_year);
+            CREATE TABLE IF NOT EXISTS imports (
+                source TEXT PRIMARY KEY,
+                survey_year INTEGER NOT NULL,
+                content_hash TEXT NOT NULL,
+                rows INTEGER NOT NULL,
+                report TEXT NOT NULL,
+                imported_at INTEGER NOT NULL
+            );"
+        ))
+        .map_err(sql_err)?;
+        Ok(SurveyDb {
+            conn,
+            path: path.to_string(),
+        })
+    }
+
+    /// CSV eines Jahrgangs einmalig importieren. Unveränderte Dateien werden
+    /// übersprungen, geänderte ersetzen die Zeilen ihres früheren Imports.
+    pub fn import_csv(&mut self, source: &str, year: i32) -> PolarsResult<ImportOutcome> {
+        let content_hash = CacheKey::default().add_file(source)?.hex();
+        let previous = self
+            .conn
+            .query_row(
+                "SELECT content_hash FROM imports WHERE source = ?1",
+                [source],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .map_err(sql_err)?;
+        if previous.as_deref() == Some(content_hash.as_str()) {
+            return Ok(ImportOutcome {
+                source: source.to_string(),
+                inserted: 0,
+                replaced: 0,
+                unchanged: true,
+            });
+        }
+
+        let (df, report) = load_data(source, year)?;
+        let report_json = serde_json::to_string(&report)
+            .map_err(|e| polars_err!(ComputeError: "Bericht nicht serialisierbar: {}", e))?;
+
+        let tx = self.conn.transaction().map_err(sql_err)?;
+        let replaced = tx
+            .execute("DELETE FROM responses WHERE source = ?1", [source])
+            .map_err(sql_err)?;
+        let inserted = insert_rows(&tx, &df, year, source)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO imports (source, survey_year, content_hash, rows, report, imported_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![source, year, content_hash, inserted as i64, report_json, unix_now()],
+        )
+        .map_err(sql_err)?;
+        tx.commit().map_err(sql_err)?;
+
+        Ok(ImportOutcome {
+            source: source.to_string(),
+            inserted,
+            replaced,
+            unchanged: false,
+        })
+    }
+
+    /// Geprüfte Zeilen (Spalten wie `records_to_dataframe`) anhängen
+    pub fn append(&mut self, df: &DataFrame, year: i32, source: &str) -> PolarsResult<usize> {
+        let tx = self.conn.transaction().map_err(sql_err)?;
+        let inserted = insert_rows(&tx, df, year, source)?;
+        tx.commit().map_err(sql_err)?;
+        Ok(inserted)
+    }
+
+    /// Jahrgänge, zu denen Antworten gespeichert sind
+    pub fn years(&self) -> PolarsResult<Vec<i32>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT survey_year FROM responses ORDER BY survey_year")
+            .map_err(sql_err)?;
+        let years = stmt
+            .query_map([], |row| row.get::<_, i32>(0))
+            .map_err(sql_err)?
+            .collect::<Result<Vec<i32>, _>>()
+            .map_err(sql_err)?;
+        Ok(years)
+    }
+
+    /// Antworten eines Jahrgangs im Schema von `load_data`. Der Bericht fasst die
+    /// Importe zusammen; später angehängte Antworten zählen als gültig.
+    pub fn read_year(&self, year: i32) -> PolarsResult<(DataFrame, ValidationReport)> {
+        let keys = Col::ALL.map(Col::key).join(", ");
+        let df = self.query(&format!(
+            "SELECT {keys}, survey_year FROM responses WHERE survey_year = {year} ORDER BY id"
+        ))?;
+
+        let mut report = ValidationReport::new(&format!("{} ({year})", self.path));
+        let mut stmt = self
+            .conn
+            .prepare("SELECT report FROM imports WHERE survey_year = ?1 ORDER BY source")
+            .map_err(sql_err)?;
+        let stored = stmt
+            .query_map([year], |row| row.get::<_, String>(0))
+            .map_err(sql_err)?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(sql_err)?;
+        for json in stored {
+            let imported: ValidationReport = serde_json::from_str(&json)
+                .map_err(|e| polars_err!(ComputeError: "Gespeicherter Bericht nicht lesbar: {}", e))?;
+            report.merge(imported);
+        }
+        for _ in report.valid_rows..df.height() {
+            report.record_valid();
+        }
+        Ok((df, report))
+    }
+
+    /// Beliebige SQL-Abfrage als DataFrame. Spalten mit einem `Col::key` als Namen
+    /// erhalten Namen und Typ wie beim CSV-Import, alle anderen einen passenden Typ.
+    pub fn query(&self, sql: &str) -> PolarsResult<DataFrame> {
+        let mut stmt = self.conn.prepare(sql).map_err(sql_err)?;
+        let names = stmt
+            .column_names()
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<String>>();
+        let mut values = vec![Vec::new(); names.len()];
+        let mut rows = stmt.query([]).map_err(sql_err)?;
+        while let Some(row) = rows.next().map_err(sql_err)? {
+            for (i, column) in values.iter_mut().enumerate() {
+                column.push(row.get::<_, SqlValue>(i).map_err(sql_err)?);
+            }
+        }
+
+        let columns = names
+            .iter()
+            .zip(values)
+            .map(|(name, values)| to_column(name, values))
+            .collect::<PolarsResult<Vec<Column>>>()?;
+        DataFrame::new(columns)
+    }
+}
+
+/// Zeilen eines DataFrames in `responses` schreiben
+fn insert_rows(tx: &rusqlite::Transaction, df: &DataFrame, year: i32, source: &str) -> PolarsResult<usize> {
+    let keys = Col::ALL.map(Col::key).join(", ");
+    let placeholders = vec!["?"; Col::ALL.len() + 3].join(", ");
+    let mut stmt = tx
+        .prepare(&format!(
+            "INSERT INTO responses (survey_year, source, imported_at, {keys}) VALUES ({placeholders})"
+        ))
+        .map_err(sql_err)?;
+
+    let columns = Col::ALL
+        .iter()
+        .map(|col| df.column(col.header()))
+        .collect::<PolarsResult<Vec<&Column>>>()?;
+    let imported_at = unix_now();
+    for i in 0..df.height() {
+        let mut row = vec![
+            SqlValue::Integer(i64::from(year)),
+            SqlValue::Text(source.to_string()),
+            SqlValue::Integer(imported_at),
+        ];
+        for column in &columns {
+            row.push(to_sql(column.get(i)?));
+        }
+        stmt.execute(params_from_iter(row)).map_err(sql_err)?;
+    }
+    Ok(df.height())
+}
+
+fn to_sql(value: AnyValue) -> SqlValue {
+    match value {
+        AnyValue::Null => SqlValue::Null,
+        AnyValue::String(text) => SqlValue::Text(text.to_string()),
+        AnyValue::StringOwned(text) => SqlValue::Text(text.to_string()),
+        AnyValue::Float64(v) => SqlValue::Real(v),
+        AnyValue::Float32(v) => SqlValue::Real(f64::from(v)),
+        other => match other.extract::<i64>() {
+            Some(v) => SqlValue::Integer(v),
+            None => SqlValue::Text(other.to_string()),
+        },
+    }
+}
+
+fn as_f64(value: &SqlValue) -> Option<f64> {
+    match value {
+        SqlValue::Integer(v) => Some(*v as f64),
+        SqlValue::Real(v) => Some(*v),
+        SqlValue::Text(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_text(value: &SqlValue) -> Option<String> {
+    match value {
+        SqlValue::Text(text) => Some(text.clone()),
+        SqlValue::Integer(v) => Some(v.to_string()),
+        SqlValue::Real(v) => Some(v.to_string()),
+        SqlValue::Blob(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        SqlValue::Null => None,
+    }
+}
+
+/// Werte einer Ergebnisspalte in eine Polars-Spalte überführen
+fn to_column(name: &str, values: Vec<SqlValue>) -> PolarsResult<Column> {
+    if name == SURVEY_YEAR_COL {
+        let years = values.iter().map(|v| as_f64(v).map(|y| y as i32)).collect::<Vec<_>>();
+        return Ok(Column::new(name.into(), years));
+    }
+    if let Some(col) = Col::from_key(name) {
+        let header = col.header().into();
+        return Ok(match sql_kind(col) {
+            SqlKind::Integer => {
+                let whole = values
+                    .iter()
+                    .map(|v| as_f64(v).map(|v| v as u32))
+                    .collect::<Vec<Option<u32>>>();
+                Column::new(header, whole)
+            }
+            SqlKind::Real => Column::new(header, values.iter().map(as_f64).collect::<Vec<_>>()),
+            SqlKind::Text => Column::new(header, values.iter().map(as_text).collect::<Vec<_>>()),
+        });
+    }
+
+    // Unbekannte Spalte (z. B. ein Aggregat): Typ aus den Werten ableiten
+    let non_null = values.iter().filter(|v| !matches!(v, SqlValue::Null));
+    let column = if non_null.clone().all(|v| matches!(v, SqlValue::Integer(_))) {
+        let ints = values
+            .iter()
+            .map(|v| match v {
+                SqlValue::Integer(v) => Some(*v),
+                _ => None,
+            })
+            .collect::<Vec<Option<i64>>>();
+        Column::new(name.into(), ints)
+    } else if non_null.clone().all(|v| matches!(v, SqlValue::Integer(_) | SqlValue::Real(_))) {
+        Column::new(name.into(), values.iter().map(as_f64).collect::<Vec<_>>())
+    } else {
+        Column::new(name.into(), values.iter().map(as_text).collect::<Vec<_>>())
+    };
+    Ok(column)
+}
+
+/// Vorhandene CSV-Dateien importieren (unveränderte werden übersprungen) und
+/// die Quellen so ersetzen, dass jeder gespeicherte Jahrgang aus der Datenbank kommt
+pub fn import_surveys(db_path: &str, files: &[(i32, String)]) -> PolarsResult<Vec<(i32, String)>> {
+    let mut db = SurveyDb::open(db_path)?;
+    for (year, path) in files {
+        if Path::new(path).exists() && !is_database_file(path) {
+            db.import_csv(path, *year)?.print_summary();
+        }
+    }
+    Ok(db
+        .years()?
+        .into_iter()
+        .map(|year| (year, db_path.to_string()))
+        .collect())
+}
+
+/// Schreibzugriffe von `append_response` nacheinander ausführen
+static APPEND_LOCK: Mutex<()> = Mutex::new(());
+
+/// Geprüfte Antwort als Zeile in der Datenbank speichern. Die CSV-Dateien sind erzeugte
+/// Referenzdateien (siehe `rust_web_app clean`) und nehmen keine Antworten auf.
+pub fn append_response(path: &str, year: i32, record: &SurveyRecord) -> PolarsResult<()> {
+    if !is_database_file(path) {
+        polars_bail!(ComputeError: "{} ist keine Datenbank; Antworten werden nur in der Datenbank gespeichert", path);
+    }
+    let df = records_to_dataframe(std::slice::from_ref(record))?;
+    let _guard = APPEND_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    SurveyDb::open(path)?.append(&df, year, SUBMISSION_SOURCE)?;
+    Ok(())
+}
@@ -0,0 +1,128 @@
+// src/survey_years.rs
+
+use crate::columns::{registry, Col, ColumnRegistry};
+use polars::prelude::*;
+use std::borrow::Cow;
+
+/// Name der Spalte mit dem Erhebungsjahr im zusammengeführten DataFrame
+pub const SURVEY_YEAR_COL: &str = "survey_year";
+
+/// Jahr, dessen Spaltennamen `SurveyRecord` verwendet
+pub const CURRENT_YEAR: i32 = 2020;
+
+/// Spaltennamen einer Erhebung und ihre Zuordnung zum gemeinsamen Schema
+#[derive(Debug, Clone)]
+pub struct YearSchema {
+    pub year: i32,
+    /// Spalten, die es in diesem Jahr gibt, mit ihrem Namen in der Datei
+    pub columns: Vec<(Col, &'static str)>,
+    /// Weitere Spalten der Datei, die zum Wert einer gemeinsamen Spalte addiert werden
+    pub added: Vec<(Col, &'static str)>,
+}
+
+impl YearSchema {
+    /// Schema für ein bekanntes Erhebungsjahr
+    pub fn for_year(year: i32) -> Option<YearSchema> {
+        let mut added = Vec::new();
+        let columns = match year {
+            2018 => vec![
+                (Col::Timestamp, "Timestamp"),
+                (Col::Age, "Age"),
+                (Col::Gender, "Gender"),
+                (Col::City, "City"),
+                (Col::Position, "Position"),
+                (Col::Experience, "Years of experience"),
+                (Col::Seniority, "Your level"),
+                (Col::Salary, "Current Salary"),
+                (Col::SalaryLastYear, "Salary one year ago"),
+                (Col::WorkLanguage, "Main language at work"),
+                (Col::CompanySize, "Company size"),
+                (Col::CompanyType, "Company type"),
+            ],
+            // "Yearly bonus" und "Yearly stocks" werden 2019 getrennt erfragt;
+            // Col::Bonus ist wie 2020 die Summe aus beidem
+            2019 => {
+                added = vec![
+                    (Col::Bonus, "Yearly stocks"),
+                    (
+                        Col::BonusLastYear,
+                        "Yearly stocks one year ago. Only answer if staying in same country",
+                    ),
+                ];
+                vec![
+                    (Col::Timestamp, "Zeitstempel"),
+                    (Col::Age, "Age"),
+                    (Col::Gender, "Gender"),
+                    (Col::City, "City"),
+                    (Col::Seniority, "Seniority level"),
+                    (Col::Position, "Position (without seniority)"),
+                    (Col::Experience, "Years of experience"),
+                    (Col::MainTech, "Your main technology / programming language"),
+                    (Col::Salary, "Yearly brutto salary (without bonus and stocks)"),
+                    (Col::Bonus, "Yearly bonus"),
+                    (
+                        Col::SalaryLastYear,
+                        "Yearly brutto salary (without bonus and stocks) one year ago. Only answer if staying in same country",
+                    ),
+                    (
+                        Col::BonusLastYear,
+                        "Yearly bonus one year ago. Only answer if staying in same country",
+                    ),
+                    (Col::VacationDays, "Number of vacation days"),
+                    (Col::WorkLanguage, "Main language at work"),
+                    (Col::CompanySize, "Company size"),
+                    (Col::CompanyType, "Company type"),
+                    (Col::ContractDuration, "Сontract duration"),
+                ]
+            }
+            CURRENT_YEAR => Col::ALL.into_iter().map(|c| (c, c.header())).collect(),
+            _ => return None,
+        };
+        Some(YearSchema { year, columns, added })
+    }
+
+    /// Globale Registry um die Spaltennamen dieses Jahres ergänzen
+    pub fn registry(&self) -> ColumnRegistry {
+        let mut registry = registry().clone();
+        for (col, header) in &self.columns {
+            registry.add_alias(*col, header);
+        }
+        registry
+    }
+
+    /// Spalten, die in der Datei dieses Jahres vorhanden sein müssen
+    pub fn required(&self) -> impl Iterator<Item = Col> + '_ {
+        self.columns.iter().map(|(col, _)| *col)
+    }
+}
+
+/// Spalte `survey_year` mit dem Erhebungsjahr anhängen
+pub fn add_survey_year(df: &mut DataFrame, year: i32) -> PolarsResult<()> {
+    let years = Column::new(SURVEY_YEAR_COL.into(), vec![year; df.height()]);
+    df.with_column(years)?;
+    Ok(())
+}
+
+/// Bereinigte Jahrgänge zu einem DataFrame zusammenführen
+pub fn merge_years(frames: Vec<DataFrame>) -> PolarsResult<DataFrame> {
+    let mut frames = frames.into_iter();
+    let mut merged = frames
+        .next()
+        .ok_or_else(|| polars_err!(NoData: "Keine Erhebung geladen"))?;
+    for df in frames {
+        merged.vstack_mut(&df)?;
+    }
+    merged.as_single_chunk_par();
+    Ok(merged)
+}
+
+/// Optional auf ein Erhebungsjahr einschränken
+pub fn filter_year(df: &DataFrame, year: Option<i32>) -> PolarsResult<Cow<'_, DataFrame>> {
+    match year {
+        None => Ok(Cow::Borrowed(df)),
+        Some(year) => {
+            let mask = df.column(SURVEY_YEAR_COL)?.i32()?.equal(year);
+            Ok(Cow::Owned(df.filter(&mask)?))
+        }
+    }
+}
@@ -0,0 +1,209 @@
+// src/tech_taxonomy.rs
+
+use crate::columns::{column, normalize_header, Col};
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::OnceLock;
+
+/// Mitgelieferte Synonymtabelle (wird beim Start durch die Datei ersetzt, falls vorhanden)
+const BUNDLED_SYNONYMS: &str = include_str!("../data/tech_synonyms.csv");
+
+/// Abgeleitete Spalte mit der kanonischen Haupttechnologie
+pub const MAIN_TECH_CANONICAL_COL: &str = "main_tech_canonical";
+/// Abgeleitete Listenspalte mit den kanonischen weiteren Technologien
+pub const OTHER_TECHS_CANONICAL_COL: &str = "other_techs_canonical";
+/// Spalte der langen Tabelle mit einer Technologie je Zeile
+pub const OTHER_TECH_COL: &str = "other_tech";
+/// Zeilennummer des Befragten in der langen Tabelle
+pub const RESPONDENT_ID_COL: &str = "respondent_id";
+
+/// Schreibweise vergleichbar machen; Leerzeichen um "/" spielen keine Rolle
+fn normalize_tech(raw: &str) -> String {
+    normalize_header(raw).replace(" / ", "/").replace("/ ", "/").replace(" /", "/")
+}
+
+/// Zuordnung von Schreibweisen zu kanonischen Technologien
+#[derive(Debug, Clone, Default)]
+pub struct TechTaxonomy {
+    synonyms: HashMap<String, Vec<String>>,
+}
+
+impl TechTaxonomy {
+    /// Tabelle im Format `synonym,canonical` lesen (`#` leitet Kommentare ein)
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, csv::Error> {
+        let mut csv_reader = csv::ReaderBuilder::new()
+            .comment(Some(b'#'))
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+
+        let mut synonyms = HashMap::new();
+        for row in csv_reader.records() {
+            let row = row?;
+            let synonym = normalize_tech(row.get(0).unwrap_or_default());
+            let canonical = row
+                .get(1)
+                .unwrap_or_default()
+                .split('|')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<String>>();
+            synonyms.insert(synonym, canonical);
+        }
+        Ok(TechTaxonomy { synonyms })
+    }
+
+    /// Die mitgelieferte Tabelle
+    pub fn bundled() -> Self {
+        Self::from_reader(BUNDLED_SYNONYMS.as_bytes()).expect("data/tech_synonyms.csv ist ungültig")
+    }
+
+    /// Eine Angabe ohne Kommas auflösen. Unbekannte Kombinationen mit "/" werden
+    /// in ihre Teile zerlegt, sonst bleibt die Angabe (getrimmt) erhalten.
+    fn canonicalize_token(&self, token: &str) -> Vec<String> {
+        let key = normalize_tech(token);
+        if key.is_empty() {
+            return Vec::new();
+        }
+        if let Some(canonical) = self.synonyms.get(&key) {
+            return canonical.clone();
+        }
+        if key.contains('/') {
+            let parts = key.split('/').collect::<Vec<&str>>();
+            if parts.iter().all(|p| self.synonyms.contains_key(*p)) {
+                return parts
+                    .into_iter()
+                    .flat_map(|p| self.synonyms[p].clone())
+                    .collect();
+            }
+        }
+        vec![token.trim().to_string()]
+    }
+
+    /// Alle kanonischen Technologien einer Freitextangabe (durch Kommas getrennt), ohne Duplikate
+    pub fn canonicalize(&self, raw: &str) -> Vec<String> {
+        let mut result = Vec::new();
+        for tech in raw.split(',').flat_map(|token| self.canonicalize_token(token)) {
+            if !result.contains(&tech) {
+                result.push(tech);
+            }
+        }
+        result
+    }
+}
+
+static TAXONOMY: OnceLock<TechTaxonomy> = OnceLock::new();
+
+/// Taxonomie einmalig beim Start festlegen
+pub fn init_taxonomy(taxonomy: TechTaxonomy) {
+    let _ = TAXONOMY.set(taxonomy);
+}
+
+/// Die prozessweite Taxonomie
+pub fn taxonomy() -> &'static TechTaxonomy {
+    TAXONOMY.get_or_init(TechTaxonomy::bundled)
+}
+
+/// Spalten `main_tech_canonical` und `other_techs_canonical` anhängen
+pub fn add_tech_columns(df: &mut DataFrame) -> PolarsResult<()> {
+    let taxonomy = taxonomy();
+
+    let main_tech = column(df, Col::MainTech)?
+        .str()?
+        .into_iter()
+        .map(|v| v.and_then(|raw| taxonomy.canonicalize(raw).into_iter().next()))
+        .collect::<StringChunked>()
+        .with_name(MAIN_TECH_CANONICAL_COL.into());
+
+    let other_techs = column(df, Col::OtherTechs)?
+        .str()?
+        .into_iter()
+        .map(|v| {
+            let techs = v.map(|raw| taxonomy.canonicalize(raw)).unwrap_or_default();
+            Some(Series::new(PlSmallStr::EMPTY, techs))
+        })
+        .collect::<ListChunked>()
+        .with_name(OTHER_TECHS_CANONICAL_COL.into());
+
+    df.with_column(main_tech.into_series())?;
+    df.with_column(other_techs.into_series())?;
+    Ok(())
+}
+
+/// Lange Tabelle: eine Zeile je Befragtem und weiterer Technologie,
+/// mit allen übrigen Spalten des Befragten
+pub fn explode_other_techs(df: &DataFrame) -> PolarsResult<DataFrame> {
+    let mut long = df
+        .with_row_index(RESPONDENT_ID_COL.into(), None)?
+        .explode([OTHER_TECHS_CANONICAL_COL])?;
+    long.rename(OTHER_TECHS_CANONICAL_COL, OTHER_TECH_COL.into())?;
+    long.drop_nulls(Some(&[OTHER_TECH_COL.to_string()]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYNONYMS: &str = "\
+# Kommentar
+synonym,canonical
+python,Python
+python 3,Python
+javascript,JavaScript
+js,JavaScript
+ts,TypeScript
+java / scala,Java|Scala
+kotlin,Kotlin
+-,
+";
+
+    fn taxonomy() -> TechTaxonomy {
+        TechTaxonomy::from_reader(SYNONYMS.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn synonyms_ignore_case_and_spacing() {
+        let taxonomy = taxonomy();
+        assert_eq!(taxonomy.canonicalize("  PYTHON 3 "), ["Python"]);
+        assert_eq!(taxonomy.canonicalize("Java/Scala"), ["Java", "Scala"]);
+        assert_eq!(taxonomy.canonicalize("java /scala"), ["Java", "Scala"]);
+    }
+
+    #[test]
+    fn lists_are_split_and_deduplicated() {
+        let taxonomy = taxonomy();
+        assert_eq!(taxonomy.canonicalize("Python, python 3, Kotlin,,"), ["Python", "Kotlin"]);
+        // Unbekannte Kombination aus bekannten Teilen
+        assert_eq!(taxonomy.canonicalize("JS / TS"), ["JavaScript", "TypeScript"]);
+        // Ein unbekannter Teil: Angabe bleibt unverändert
+        assert_eq!(taxonomy.canonicalize("JS/Elm"), ["JS/Elm"]);
+        assert_eq!(taxonomy.canonicalize(" Haskell "), ["Haskell"]);
+    }
+
+    #[test]
+    fn empty_mapping_means_no_answer() {
+        let taxonomy = taxonomy();
+        assert!(taxonomy.canonicalize("-").is_empty());
+        assert_eq!(taxonomy.canonicalize("-, Kotlin"), ["Kotlin"]);
+        assert!(taxonomy.canonicalize("  ").is_empty());
+    }
+
+    #[test]
+    fn columns_and_long_table_use_the_bundled_table() {
+        let mut df = df!(
+            Col::MainTech.header() => [Some("python"), Some("JS"), None],
+            Col::OtherTechs.header() => [Some("Kotlin, javascript"), None, Some("Python")],
+        )
+        .unwrap();
+        add_tech_columns(&mut df).unwrap();
+        let main = df.column(MAIN_TECH_CANONICAL_COL).unwrap().str().unwrap();
+        assert_eq!(main.into_iter().collect::<Vec<_>>(), [Some("Python"), Some("JavaScript"), None]);
+
+        let long = explode_other_techs(&df).unwrap();
+        let ids = long.column(RESPONDENT_ID_COL).unwrap().u32().unwrap();
+        let techs = long.column(OTHER_TECH_COL).unwrap().str().unwrap();
+        assert_eq!(ids.into_iter().flatten().collect::<Vec<_>>(), [0, 0, 2]);
+        assert_eq!(techs.into_iter().flatten().collect::<Vec<_>>(), ["Kotlin", "JavaScript", "Python"]);
+    }
+}
@@ -0,0 +1,289 @@
+// src/validation.rs
+
+use crate::columns::Col;
+use crate::data_analysis::SurveyRecord;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Untere und obere Grenze für ein plausibles Alter
+const AGE_RANGE: (f64, f64) = (14.0, 100.0);
+
+/// Mehr Urlaubstage als Tage im Jahr sind nicht möglich
+const MAX_VACATION_DAYS: u32 = 365;
+
+/// Obergrenzen für neue Antworten (wie in `CleaningPipeline::survey_2020`)
+const MAX_SUBMITTED_SALARY: f64 = 500_000.0;
+const MAX_SUBMITTED_EXPERIENCE: f64 = 50.0;
+
+/// Erlaubte Werte der Auswahlfelder für neue Antworten
+pub const SUBMISSION_CATEGORIES: [(Col, &[&str]); 7] = [
+    (Col::Gender, &["Male", "Female", "Diverse"]),
+    (Col::Seniority, &["Junior", "Middle", "Senior", "Lead", "Head", "Principal"]),
+    (
+        Col::EmploymentStatus,
+        &[
+            "Full-time employee",
+            "Part-time employee",
+            "Self-employed (freelancer)",
+            "Founder",
+            "Working Student",
+            "Intern",
+        ],
+    ),
+    (Col::ContractDuration, &["Unlimited contract", "Temporary contract"]),
+    (Col::CompanySize, &["up to 10", "11-50", "51-100", "101-1000", "1000+"]),
+    (
+        Col::CompanyType,
+        &[
+            "Product",
+            "Startup",
+            "Consulting / Agency",
+            "Bank",
+            "Corporation",
+            "E-commerce",
+            "Fintech",
+            "Media",
+            "University",
+            "Other",
+        ],
+    ),
+    (Col::LostJobCovid, &["Yes", "No"]),
+];
+
+/// Art eines Zeilenfehlers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowErrorKind {
+    /// Wert konnte nicht in den Zieltyp umgewandelt werden
+    InvalidValue,
+    /// Alter außerhalb des plausiblen Bereichs
+    ImpossibleAge,
+    /// Negativer Betrag oder negative Jahresangabe
+    NegativeValue,
+    /// Mehr Berufsjahre als Lebensjahre, zu viele Urlaubstage usw.
+    Implausible,
+    /// Zeile passt nicht zur Kopfzeile
+    MalformedRow,
+    /// Pflichtangabe fehlt (beim Laden das Gehalt)
+    MissingValue,
+    /// Wert eines Auswahlfelds ist nicht erlaubt
+    UnknownCategory,
+}
+
+/// Ein Fehler in einer Zeile der Eingabedatei
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowError {
+    /// Zeilennummer in der Datei (Kopfzeile = 1)
+    pub line: u64,
+    pub column: Option<String>,
+    pub kind: RowErrorKind,
+    pub message: String,
+}
+
+/// Ergebnis der zeilenweisen Prüfung beim Laden
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub source: String,
+    pub total_rows: usize,
+    pub valid_rows: usize,
+    pub dropped_rows: usize,
+    pub errors_by_kind: BTreeMap<RowErrorKind, usize>,
+    pub errors_by_column: BTreeMap<String, usize>,
+    pub errors: Vec<RowError>,
+    /// Nicht lesbare Werte optionaler Zahlenfelder; die Zeile bleibt mit leerem Wert erhalten
+    #[serde(default)]
+    pub warnings_by_column: BTreeMap<String, usize>,
+    #[serde(default)]
+    pub warnings: Vec<RowError>,
+}
+
+impl ValidationReport {
+    pub fn new(source: &str) -> Self {
+        ValidationReport {
+            source: source.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Eine gültige Zeile zählen
+    pub fn record_valid(&mut self) {
+        self.total_rows += 1;
+        self.valid_rows += 1;
+    }
+
+    /// Eine verworfene Zeile samt ihrer Fehler zählen
+    pub fn record_dropped(&mut self, errors: Vec<RowError>) {
+        self.total_rows += 1;
+        self.dropped_rows += 1;
+        for error in errors {
+            *self.errors_by_kind.entry(error.kind).or_insert(0) += 1;
+            if let Some(column) = &error.column {
+                *self.errors_by_column.entry(column.clone()).or_insert(0) += 1;
+            }
+            self.errors.push(error);
+        }
+    }
+
+    /// Warnungen zu einer behaltenen Zeile festhalten
+    pub fn record_warnings(&mut self, warnings: Vec<RowError>) {
+        for warning in warnings {
+            if let Some(column) = &warning.column {
+                *self.warnings_by_column.entry(column.clone()).or_insert(0) += 1;
+            }
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Zählungen und Fehler eines weiteren Berichts übernehmen
+    pub fn merge(&mut self, other: ValidationReport) {
+        self.total_rows += other.total_rows;
+        self.valid_rows += other.valid_rows;
+        self.dropped_rows += other.dropped_rows;
+        for (kind, count) in other.errors_by_kind {
+            *self.errors_by_kind.entry(kind).or_insert(0) += count;
+        }
+        for (column, count) in other.errors_by_column {
+            *self.errors_by_column.entry(column).or_insert(0) += count;
+        }
+        self.errors.extend(other.errors);
+        for (column, count) in other.warnings_by_column {
+            *self.warnings_by_column.entry(column).or_insert(0) += count;
+        }
+        self.warnings.extend(other.warnings);
+    }
+
+    /// Kurze Zusammenfassung auf stdout ausgeben
+    pub fn print_summary(&self) {
+        println!("=== Validierung ({}) ===", self.source);
+        println!(
+            "Zeilen: {}, gültig: {}, verworfen: {}",
+            self.total_rows, self.valid_rows, self.dropped_rows
+        );
+        for (kind, count) in &self.errors_by_kind {
+            println!("  {kind:?}: {count}");
+        }
+        for (column, count) in &self.errors_by_column {
+            println!("  Spalte '{column}': {count}");
+        }
+        if !self.warnings.is_empty() {
+            println!("Als leer gelesene Werte: {}", self.warnings.len());
+            for (column, count) in &self.warnings_by_column {
+                println!("  Spalte '{column}': {count}");
+            }
+        }
+    }
+}
+
+/// Inhaltliche Prüfung eines bereits typisierten Datensatzes
+pub fn check_record(record: &SurveyRecord, line: u64) -> Vec<RowError> {
+    let mut errors = Vec::new();
+    let mut push = |col: Col, kind: RowErrorKind, message: String| {
+        errors.push(RowError {
+            line,
+            column: Some(col.header().to_string()),
+            kind,
+            message,
+        });
+    };
+
+    if let Some(age) = record.age {
+        if age < AGE_RANGE.0 || age > AGE_RANGE.1 {
+            push(Col::Age, RowErrorKind::ImpossibleAge, format!("Alter {age} ist nicht plausibel"));
+        }
+    }
+
+    let amounts = [
+        (Col::Salary, record.yearly_brutto_salary),
+        (Col::Bonus, record.yearly_bonus_stocks),
+        (Col::SalaryLastYear, record.yearly_brutto_salary_last),
+        (Col::BonusLastYear, record.bonus_stocks_last),
+        (Col::Experience, record.total_experience),
+        (Col::ExperienceGermany, record.experience_germany),
+    ];
+    for (col, value) in amounts {
+        if let Some(v) = value.filter(|v| *v < 0.0) {
+            push(col, RowErrorKind::NegativeValue, format!("Negativer Wert {v}"));
+        }
+    }
+
+    if let (Some(age), Some(experience)) = (record.age, record.total_experience) {
+        if experience > age {
+            push(
+                Col::Experience,
+                RowErrorKind::Implausible,
+                format!("{experience} Jahre Erfahrung bei Alter {age}"),
+            );
+        }
+    }
+
+    if let Some(days) = record.vacation_days.filter(|d| *d > MAX_VACATION_DAYS) {
+        push(
+            Col::VacationDays,
+            RowErrorKind::Implausible,
+            format!("{days} Urlaubstage"),
+        );
+    }
+
+    errors
+}
+
+/// Neue Antwort (z. B. aus `POST /responses`) prüfen: Bereiche wie beim Laden, dazu
+/// Pflichtangaben, Obergrenzen und erlaubte Kategorien. Kategorien werden unabhängig
+/// von Groß-/Kleinschreibung erkannt und auf die erlaubte Schreibweise gebracht.
+pub fn validate_submission(record: &mut SurveyRecord) -> Result<(), Vec<RowError>> {
+    let mut errors = check_record(record, 0);
+    let mut push = |col: Col, kind: RowErrorKind, message: String| {
+        errors.push(RowError {
+            line: 0,
+            column: Some(col.header().to_string()),
+            kind,
+            message,
+        });
+    };
+
+    // Ohne diese Angaben kann die Antwort nicht ausgewertet werden
+    let required = [
+        (Col::Experience, record.total_experience.is_some()),
+        (Col::Seniority, record.seniority_level.as_deref().is_some_and(|s| !s.trim().is_empty())),
+        (Col::Salary, record.yearly_brutto_salary.is_some()),
+    ];
+    for (col, present) in required {
+        if !present {
+            push(col, RowErrorKind::MissingValue, format!("'{}' ist eine Pflichtangabe", col.key()));
+        }
+    }
+
+    if let Some(salary) = record.yearly_brutto_salary.filter(|v| *v > MAX_SUBMITTED_SALARY) {
+        push(Col::Salary, RowErrorKind::Implausible, format!("Gehalt {salary} über {MAX_SUBMITTED_SALARY}"));
+    }
+    if let Some(years) = record.total_experience.filter(|v| *v > MAX_SUBMITTED_EXPERIENCE) {
+        push(
+            Col::Experience,
+            RowErrorKind::Implausible,
+            format!("{years} Jahre Erfahrung über {MAX_SUBMITTED_EXPERIENCE}"),
+        );
+    }
+
+    for (col, allowed) in SUBMISSION_CATEGORIES {
+        let Some(value) = record.text_mut(col) else { continue };
+        let Some(text) = value.as_deref() else { continue };
+        if text.trim().is_empty() {
+            *value = None;
+            continue;
+        }
+        match allowed.iter().find(|a| a.eq_ignore_ascii_case(text.trim())) {
+            Some(canonical) => *value = Some(canonical.to_string()),
+            None => push(
+                col,
+                RowErrorKind::UnknownCategory,
+                format!("'{text}' ist nicht erlaubt, erlaubt: {}", allowed.join(", ")),
+            ),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
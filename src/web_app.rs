@@ -1,200 +1,1125 @@
 use axum::{
-    extract::{Query, State},
+    extract::{rejection::JsonRejection, DefaultBodyLimit, Multipart, Path, Query, State},
+    http::StatusCode,
     response::{Html, IntoResponse},
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use serde::Deserialize;
 use serde_json::json;
-use std::sync::{Arc, Mutex};
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use polars::prelude::*;
-use crate::data_analysis::{calculate_summary_statistics, calculate_distribution};
-use polars::lazy::prelude::*;
-use polars::lazy::dsl::*;
+use crate::bootstrap::BootstrapConfig;
+use crate::data_analysis::{
+    calculate_summary_statistics, calculate_distribution, calculate_group_distribution, calculate_grouped_summary,
+    calculate_tech_summary, ensure_rows, load_data_from_reader, numeric_column, numeric_series, prepare_survey,
+    SurveyRecord,
+    DEFAULT_GROUP_DISTRIBUTION_BINS,
+};
+use crate::datasets::{
+    unprepared_dataset_name, variant_dataset_name, Dataset, DatasetInfo, DatasetKind, DatasetRegistry, DEFAULT_DATASET,
+};
+use crate::density::{calculate_density, Bandwidth, Kernel, DEFAULT_GRID_POINTS};
+use crate::error::{AppError, AppResult};
+use crate::gazetteer::{CITY_NORMALIZED_COL, STATE_COL};
+use crate::columns::{registry, Col};
+use crate::compensation::{parse_metric, resolve_metric, Metric};
+use crate::correlations::{calculate_correlations, DEFAULT_CATEGORICAL_COLUMNS, DEFAULT_NUMERIC_COLUMNS};
+use crate::imputation::ImputationPolicy;
+use crate::inference::compare_groups;
+use crate::reload::{apply_reload, ReloadStatus, SurveySources};
+use crate::stats::{
+    column_profile, group_stats, parse_quantiles, Aggregation, GroupStatsRequest, DEFAULT_QUANTILES, DEFAULT_TOP_K,
+};
+use crate::storage::{append_response, is_database_file, submission_timestamp};
+use crate::outliers::{apply_rule, list_outliers, OutlierMethod, OutlierRule, DEFAULT_IQR_K};
+use crate::raises::{
+    raise_analysis, RaiseRequest, DEFAULT_MAX_DECREASE_PCT, DEFAULT_MAX_INCREASE_PCT, DEFAULT_RAISE_BREAKDOWNS,
+};
+use crate::survey_years::{filter_year, merge_years, CURRENT_YEAR};
+use crate::validation::{validate_submission, ValidationReport, SUBMISSION_CATEGORIES};
+use rust_web_app::binning::BinStrategy;
+use rust_web_app::{calculate_histogram, HistogramData};
 
+/// Maximale Größe einer hochgeladenen CSV-Datei (10 MiB)
+const MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
 
+/// Gruppen mit weniger Befragten werden unter "other" zusammengefasst
+const DEFAULT_MIN_GROUP_SIZE: usize = 10;
 
+/// Standard-Klassenbreite für `/histogram` in EUR
+const DEFAULT_HISTOGRAM_BIN_SIZE: f64 = 10_000.0;
 
+/// Standard-Klassenbreite für `/raises` in Prozentpunkten
+const DEFAULT_RAISE_BIN_WIDTH: f64 = 5.0;
 
 
 
 
-pub async fn show_gender_distribution(
-    State(state): State<Arc<Mutex<AppState>>>
-) -> Html<String> {
-    // 1) DataFrame aus dem State holen
-    let guard = state.lock().unwrap();
-    let df = &guard.df;
 
-    // 2) Spaltennamen definieren (Gender, Salary)
-    let gender_col = "Gender";
-    let salary_col = "Yearly brutto salary (without bonus and stocks) in EUR";
 
-    // 3) Prüfen, ob Spalten existieren
-    let col_names = df.get_column_names();
-    if !col_names.iter().any(|s| s.as_str() == gender_col) ||
-        !col_names.iter().any(|s| s.as_str() == salary_col) {
-        return Html("<h1>ERROR: Gender or Salary column not found</h1>".to_string());
-    }
 
-    // 4) Gender-Spalte als Utf8Chunked holen
-    let gender_series = df.column(gender_col).unwrap();
-    let gender_utf8 = match gender_series.utf8() {
-        Ok(utf) => utf,
-        Err(e) => {
-            return Html(format!("<h1>ERROR: {}</h1>", e));
-        }
-    };
 
-    // Hilfsfunktion: Verteilung berechnen
-    // (Bins und Frequenzen für ein Array von f64)
-    let calc_distribution = |values: &[f64], bin_count: usize| -> (Vec<f64>, Vec<usize>) {
-        if values.is_empty() {
-            return (vec![], vec![]);
-        }
-        let min_val = values.iter().copied().fold(f64::INFINITY, f64::min);
-        let max_val = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
-        let bin_size = (max_val - min_val).max(1.0) / bin_count as f64;
-        let mut freq = vec![0; bin_count];
 
-        for &val in values {
-            let mut idx = ((val - min_val) / bin_size).floor() as isize;
-            if idx < 0 {
-                idx = 0;
-            }
-            if idx as usize >= bin_count {
-                idx = bin_count as isize - 1;
-            }
-            freq[idx as usize] += 1;
-        }
+/// Gruppe, Kennzahl und Klassen für `/distribution/by/data`, z. B. `?group=Gender&bins=30`
+/// oder `?group=Gender&strategy=fd`
+#[derive(Deserialize)]
+pub struct GroupDistributionParams {
+    dataset: Option<String>,
+    year: Option<i32>,
+    group: String,
+    metric: Option<String>,
+    min_count: Option<usize>,
+    #[serde(flatten)]
+    outliers: OutlierParams,
+    #[serde(flatten)]
+    binning: BinParams,
+}
 
-        let bins = (0..bin_count)
-            .map(|i| min_val + i as f64 * bin_size)
-            .collect::<Vec<f64>>();
-        (bins, freq)
-    };
+/// Verteilung einer Kennzahl (Standard: Gehalt) je Gruppe auf gemeinsamen Klassengrenzen
+pub async fn get_group_distribution(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<GroupDistributionParams>,
+) -> AppResult<Json<serde_json::Value>> {
+    let strategy = params.binning.strategy(BinStrategy::FixedCount(DEFAULT_GROUP_DISTRIBUTION_BINS))?;
+    let guard = lock_state(&state);
+    let df = prepare(&guard.datasets.get(params.dataset.as_deref())?.df, params.year, &params.outliers)?;
+    let group_col = registry().resolve_name(&df, &params.group)?;
+    let metric_col = resolve_metric(&df, params.metric.as_deref().unwrap_or(Col::Salary.key()))?;
+    Ok(Json(calculate_group_distribution(
+        &df,
+        &group_col,
+        &metric_col,
+        &strategy,
+        params.min_count.unwrap_or(1),
+    )?))
+}
 
-    // === Männer-Gehälter filtern ===
-    let men_mask = gender_utf8.equal("M");             // -> BooleanChunked
-    let men_df = df.filter(&men_mask).unwrap();        // -> DataFrame
-    let men_salaries = men_df
-        .column(salary_col).unwrap()
-        .f64().unwrap()
-        .into_no_null_iter()
-        .collect::<Vec<f64>>();
-
-    // === Frauen-Gehälter filtern ===
-    let women_mask = gender_utf8.equal("F");
-    let women_df = df.filter(&women_mask).unwrap();
-    let women_salaries = women_df
-        .column(salary_col).unwrap()
-        .f64().unwrap()
-        .into_no_null_iter()
-        .collect::<Vec<f64>>();
-
-    // === Verteilung berechnen ===
-    let bin_count = 10;
-    let (men_bins, men_freq) = calc_distribution(&men_salaries, bin_count);
-    let (women_bins, women_freq) = calc_distribution(&women_salaries, bin_count);
-
-    // 5) HTML zusammenbauen
-    // - Wir betten Plotly via CDN ein
-    // - Erzeugen 2 Balken (traceMen, traceWomen)
-    // - Verwenden barmode: 'group'
-    let html = format!(r#"
+/// Seite zum Vergleich der Verteilung je Gruppe, z. B. `/distribution/by?group=Gender&mode=density`.
+/// Alle Query-Parameter außer `mode` gehen unverändert an `/distribution/by/data`.
+pub async fn show_group_distribution() -> Html<&'static str> {
+    Html(r#"
     <!DOCTYPE html>
-    <html>
+    <html lang="de">
       <head>
         <meta charset="UTF-8"/>
-        <title>Gender Salary Distribution</title>
+        <title>Distribution by Group</title>
         <script src="https://cdn.plot.ly/plotly-2.18.2.min.js"></script>
         <style>
-          body {{
+          body {
             font-family: Arial, sans-serif;
             background: #f4f4f9;
             color: #333;
             text-align: center;
-          }}
-          #chart {{
+          }
+          #chart {
             width: 90%;
-            max-width: 800px;
+            max-width: 900px;
             height: 600px;
             margin: 0 auto;
-          }}
+          }
+          select {
+            padding: 6px;
+            font-size: 16px;
+          }
+          #error {
+            color: #c0392b;
+          }
         </style>
       </head>
       <body>
-        <h1>Gehaltverteilung für M vs. F</h1>
+        <h1 id="title">Gehaltsverteilung je Gruppe</h1>
+        <label for="mode">Darstellung:</label>
+        <select id="mode" onchange="render()">
+          <option value="overlay">Überlagert</option>
+          <option value="group">Nebeneinander</option>
+          <option value="density">Dichte (normiert)</option>
+        </select>
+        <p id="error"></p>
         <div id="chart"></div>
 
         <script>
-          // Rust-Daten einbetten
-          let menBins = {men_bins:?};
-          let menFreq = {men_freq:?};
-          let womenBins = {women_bins:?};
-          let womenFreq = {women_freq:?};
-
-          // Plotly-Traces
-          let traceMen = {{
-            x: menBins,
-            y: menFreq,
-            type: 'bar',
-            name: 'Männlich',
-            marker: {{ color: 'blue' }}
-          }};
-
-          let traceWomen = {{
-            x: womenBins,
-            y: womenFreq,
-            type: 'bar',
-            name: 'Weiblich',
-            marker: {{ color: 'red' }}
-          }};
-
-          let data = [traceMen, traceWomen];
-          let layout = {{
-            title: 'Salary Distribution by Gender',
-            xaxis: {{ title: 'Salary (EUR)' }},
-            yaxis: {{ title: 'Frequency' }},
-            barmode: 'group'
-          }};
-
-          Plotly.newPlot('chart', data, layout);
+          const params = new URLSearchParams(location.search);
+          if (!params.has('group')) {
+            params.set('group', 'gender');
+          }
+          const mode = params.get('mode');
+          if (mode) {
+            document.getElementById('mode').value = mode;
+            params.delete('mode');
+          }
+          let data = null;
+
+          function render() {
+            const mode = document.getElementById('mode').value;
+            const density = mode === 'density';
+            const traces = data.groups.map(group => ({
+              x: data.centers,
+              y: density ? group.density : group.counts,
+              width: mode === 'group' ? undefined : data.edges.slice(1).map((edge, i) => edge - data.edges[i]),
+              type: 'bar',
+              name: `${group.name} (n = ${group.count})`,
+              opacity: mode === 'group' ? 1.0 : 0.5,
+            }));
+            const layout = {
+              title: `${data.metric} nach ${data.group_by}`,
+              xaxis: { title: data.metric },
+              yaxis: { title: density ? 'Dichte' : 'Anzahl' },
+              barmode: mode === 'group' ? 'group' : 'overlay',
+              bargap: mode === 'group' ? 0.1 : 0,
+            };
+            Plotly.newPlot('chart', traces, layout);
+          }
+
+          async function load() {
+            const response = await fetch('/distribution/by/data?' + params.toString());
+            const body = await response.json();
+            if (!response.ok) {
+              document.getElementById('error').innerText = body.message;
+              return;
+            }
+            data = body;
+            document.getElementById('title').innerText = `Verteilung nach ${data.group_by}`;
+            render();
+          }
+
+          load();
         </script>
       </body>
     </html>
-    "#);
+    "#)
+}
 
-    // 6) Als Html<String> zurückgeben
-    Html(html)
+pub async fn eda_summary(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<SummaryParams>,
+) -> AppResult<Json<serde_json::Value>> {
+    let bootstrap = params.bootstrap.config()?;
+    let metrics = match params.metric.as_deref() {
+        Some(metric) => vec![parse_metric(Some(metric))?],
+        None => Metric::ALL.to_vec(),
+    };
+    let df = prepared(&state, params.dataset.as_deref(), params.year, &params.outliers)?;
+    let summary = run_blocking(move || calculate_summary_statistics(&df, &metrics, bootstrap.as_ref())).await?;
+    Ok(Json(summary))
 }
 
+/// EDA-Kennzahlen je vereinheitlichtem Ort
+pub async fn eda_summary_by_city(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<GroupSummaryParams>,
+) -> AppResult<Json<serde_json::Value>> {
+    let bootstrap = params.bootstrap.config()?;
+    let metric = parse_metric(params.metric.as_deref())?;
+    let df = prepared(&state, params.dataset.as_deref(), params.year, &params.outliers)?;
+    let min_count = params.min_count.unwrap_or(DEFAULT_MIN_GROUP_SIZE);
+    Ok(Json(run_blocking(move || calculate_grouped_summary(&df, CITY_NORMALIZED_COL, metric, min_count, bootstrap.as_ref())).await?))
+}
 
+/// EDA-Kennzahlen je Bundesland
+pub async fn eda_summary_by_state(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<GroupSummaryParams>,
+) -> AppResult<Json<serde_json::Value>> {
+    let bootstrap = params.bootstrap.config()?;
+    let metric = parse_metric(params.metric.as_deref())?;
+    let df = prepared(&state, params.dataset.as_deref(), params.year, &params.outliers)?;
+    let min_count = params.min_count.unwrap_or(DEFAULT_MIN_GROUP_SIZE);
+    Ok(Json(run_blocking(move || calculate_grouped_summary(&df, STATE_COL, metric, min_count, bootstrap.as_ref())).await?))
+}
 
+/// Endpunkt für Gehälter je kanonischer Technologie
+pub async fn tech_summary(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<YearParams>,
+) -> AppResult<Json<serde_json::Value>> {
+    let metric = parse_metric(params.metric.as_deref())?;
+    let guard = lock_state(&state);
+    let df = prepare(&guard.datasets.get(params.dataset.as_deref())?.df, params.year, &params.outliers)?;
+    Ok(Json(calculate_tech_summary(&df, metric)?))
+}
 
+/// Endpunkt für Verteilungsdaten
+pub async fn get_distribution_data(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<DistributionParams>,
+) -> AppResult<Json<serde_json::Value>> {
+    let strategy = params.binning.strategy(BinStrategy::default())?;
+    let metric = parse_metric(params.metric.as_deref())?;
+    let guard = lock_state(&state);
+    let df = prepare(&guard.datasets.get(params.dataset.as_deref())?.df, params.year, &params.outliers)?;
+    let distribution = calculate_distribution(&df, metric, &strategy)?;
+    Ok(Json(distribution))
+}
 
+/// Kennzahlen einer beliebigen Spalte; mit `columns` eine Liste von Profilen
+pub async fn get_column_stats(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<StatsParams>,
+) -> AppResult<Json<serde_json::Value>> {
+    let quantiles = match &params.quantiles {
+        Some(spec) => parse_quantiles(spec).map_err(AppError::InvalidParameter)?,
+        None => DEFAULT_QUANTILES.to_vec(),
+    };
+    let top_k = params.top.unwrap_or(DEFAULT_TOP_K);
+
+    let guard = lock_state(&state);
+    let df = prepare(&guard.datasets.get(params.dataset.as_deref())?.df, params.year, &params.outliers)?;
+    ensure_rows(&df)?;
+    match (&params.column, &params.columns, &params.metric) {
+        (Some(column), None, None) => Ok(Json(column_profile(&df, column, &quantiles, top_k)?)),
+        (None, None, Some(metric)) => {
+            let column = parse_metric(Some(metric))?.column(&df)?;
+            Ok(Json(column_profile(&df, &column, &quantiles, top_k)?))
+        }
+        (None, Some(columns), None) => {
+            let profiles = columns
+                .split(',')
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(|column| column_profile(&df, column, &quantiles, top_k))
+                .collect::<AppResult<Vec<_>>>()?;
+            Ok(Json(json!(profiles)))
+        }
+        _ => Err(AppError::InvalidParameter(
+            "genau einer der Parameter 'column', 'columns' oder 'metric' ist nötig".to_string(),
+        )),
+    }
+}
 
+/// Kennzahlen einer numerischen Spalte je Gruppe (Standard: Median-Gehalt)
+pub async fn get_group_stats(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<GroupStatsParams>,
+) -> AppResult<Json<serde_json::Value>> {
+    let aggregations = params
+        .agg
+        .as_deref()
+        .unwrap_or("count,median,mean")
+        .split(',')
+        .filter(|token| !token.trim().is_empty())
+        .map(Aggregation::parse)
+        .collect::<Result<Vec<_>, String>>()
+        .map_err(AppError::InvalidParameter)?;
+    let descending = match params.order.as_deref() {
+        None => None,
+        Some("desc") => Some(true),
+        Some("asc") => Some(false),
+        Some(other) => {
+            return Err(AppError::InvalidParameter(format!("order '{other}' ist weder 'asc' noch 'desc'")));
+        }
+    };
+    let request = GroupStatsRequest {
+        by: params.by.split(',').map(str::trim).filter(|b| !b.is_empty()).map(str::to_string).collect(),
+        metric: params.metric.unwrap_or_else(|| Col::Salary.key().to_string()),
+        aggregations,
+        sort: params.sort,
+        descending,
+        min_size: params.min_size.unwrap_or(DEFAULT_MIN_GROUP_SIZE),
+        top: params.top,
+        bootstrap: params.bootstrap.config()?,
+    };
 
-pub async fn eda_summary(State(state): State<Arc<Mutex<AppState>>>) -> Json<serde_json::Value> {
-    let guard = state.lock().unwrap();
-    let summary = calculate_summary_statistics(&guard.df);
-    Json(summary)
+    let df = prepared(&state, params.dataset.as_deref(), params.year, &params.outliers)?;
+    ensure_rows(&df)?;
+    Ok(Json(run_blocking(move || group_stats(&df, &request)).await?))
 }
 
-/// Endpunkt für Verteilungsdaten
-pub async fn get_distribution_data(State(state): State<Arc<Mutex<AppState>>>) -> Json<serde_json::Value> {
-    let guard = state.lock().unwrap();
-    let distribution = calculate_distribution(&guard.df);
-    Json(distribution)
+/// Signifikanztests einer Kennzahl (Standard: Gehalt) zwischen den Werten einer Gruppenspalte
+pub async fn compare(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<CompareParams>,
+) -> AppResult<Json<serde_json::Value>> {
+    let only = params
+        .groups
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|g| !g.is_empty())
+        .map(str::to_string)
+        .collect::<Vec<String>>();
+
+    let guard = lock_state(&state);
+    let df = prepare(&guard.datasets.get(params.dataset.as_deref())?.df, params.year, &params.outliers)?;
+    ensure_rows(&df)?;
+    let group_col = registry().resolve_name(&df, &params.group)?;
+    let metric_col = resolve_metric(&df, params.column.as_deref().unwrap_or(Col::Salary.key()))?;
+    Ok(Json(compare_groups(
+        &df,
+        &metric_col,
+        &group_col,
+        &only,
+        params.min_size.unwrap_or(DEFAULT_MIN_GROUP_SIZE),
+    )?))
+}
+
+/// Kerndichteschätzung einer Spalte (Standard: Gehalt), optional je Gruppe
+pub async fn get_density(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<DensityParams>,
+) -> AppResult<Json<serde_json::Value>> {
+    let kernel = Kernel::parse(params.kernel.as_deref().unwrap_or("gaussian")).map_err(AppError::InvalidParameter)?;
+    let bandwidth =
+        Bandwidth::parse(params.bandwidth.as_deref().unwrap_or("silverman")).map_err(AppError::InvalidParameter)?;
+
+    let guard = lock_state(&state);
+    let df = prepare(&guard.datasets.get(params.dataset.as_deref())?.df, params.year, &params.outliers)?;
+    ensure_rows(&df)?;
+    let metric_col = resolve_metric(&df, params.column.as_deref().unwrap_or(Col::Salary.key()))?;
+    let group_col = params.group.as_deref().map(|group| registry().resolve_name(&df, group)).transpose()?;
+    Ok(Json(calculate_density(
+        &df,
+        &metric_col,
+        group_col.as_deref(),
+        kernel,
+        bandwidth,
+        params.points.unwrap_or(DEFAULT_GRID_POINTS),
+        params.min_count.unwrap_or(DEFAULT_MIN_GROUP_SIZE),
+    )?))
+}
+
+/// Gehaltsänderung gegenüber dem Vorjahr mit Verteilung, Aufschlüsselungen und auffälligen Zeilen
+pub async fn get_raises(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<RaiseParams>,
+) -> AppResult<Json<serde_json::Value>> {
+    let by = match params.by.as_deref() {
+        Some(by) => by.split(',').map(str::trim).filter(|b| !b.is_empty()).map(str::to_string).collect(),
+        None => DEFAULT_RAISE_BREAKDOWNS.iter().map(|b| b.to_string()).collect(),
+    };
+    let request = RaiseRequest {
+        by,
+        min_size: params.min_size.unwrap_or(DEFAULT_MIN_GROUP_SIZE),
+        max_increase_pct: params.max_increase.unwrap_or(DEFAULT_MAX_INCREASE_PCT),
+        max_decrease_pct: params.max_decrease.unwrap_or(DEFAULT_MAX_DECREASE_PCT),
+        include_flagged: params.include_flagged.unwrap_or(false),
+        strategy: params.binning.strategy(BinStrategy::FixedWidth(DEFAULT_RAISE_BIN_WIDTH))?,
+    };
+
+    let guard = lock_state(&state);
+    let df = prepare(&guard.datasets.get(params.dataset.as_deref())?.df, params.year, &params.outliers)?;
+    Ok(Json(raise_analysis(&df, &request)?))
+}
+
+/// Seite zur Gehaltsänderung gegenüber dem Vorjahr, z. B. `/raises?by=seniority`.
+/// Alle Query-Parameter außer `by` gehen unverändert an `/raises/data`.
+pub async fn show_raises() -> Html<&'static str> {
+    Html(r#"
+    <!DOCTYPE html>
+    <html lang="de">
+      <head>
+        <meta charset="UTF-8"/>
+        <title>Gehaltsänderung zum Vorjahr</title>
+        <script src="https://cdn.plot.ly/plotly-2.18.2.min.js"></script>
+        <style>
+          body {
+            font-family: Arial, sans-serif;
+            background: #f4f4f9;
+            color: #333;
+            text-align: center;
+          }
+          .chart {
+            width: 90%;
+            max-width: 900px;
+            height: 450px;
+            margin: 0 auto;
+          }
+          select {
+            padding: 6px;
+            font-size: 16px;
+          }
+          #error {
+            color: #c0392b;
+          }
+        </style>
+      </head>
+      <body>
+        <h1>Gehaltsänderung zum Vorjahr</h1>
+        <p id="summary"></p>
+        <p id="error"></p>
+        <div id="histogram" class="chart"></div>
+        <label for="breakdown">Aufschlüsselung:</label>
+        <select id="breakdown" onchange="renderBreakdown()"></select>
+        <div id="breakdown-chart" class="chart"></div>
+
+        <script>
+          const params = new URLSearchParams(location.search);
+          const selected = params.get('by');
+          let data = null;
+
+          function renderBreakdown() {
+            const name = document.getElementById('breakdown').value;
+            const groups = data.breakdowns[name].groups;
+            Plotly.newPlot('breakdown-chart', [{
+              x: groups.map(group => group.name),
+              y: groups.map(group => group.median_pct),
+              text: groups.map(group => `n = ${group.count}`),
+              type: 'bar',
+              marker: { color: '#3498db' },
+            }], {
+              title: `Median der Änderung nach ${data.breakdowns[name].column}`,
+              yaxis: { title: 'Änderung in %' },
+            });
+          }
+
+          async function load() {
+            const response = await fetch('/raises/data?' + params.toString());
+            const body = await response.json();
+            if (!response.ok) {
+              document.getElementById('error').innerText = body.message;
+              return;
+            }
+            data = body;
+            const pct = data.raise_pct;
+            document.getElementById('summary').innerText =
+              `${data.included} von ${data.respondents} Antworten ausgewertet, ` +
+              `${data.flagged_count} unplausibel. Median: ${pct.median.toFixed(1)} %, ` +
+              `Erhöhung bei ${(pct.share_increased * 100).toFixed(0)} %.`;
+
+            Plotly.newPlot('histogram', [{
+              x: data.histogram.centers,
+              y: data.histogram.counts,
+              width: data.histogram.edges.slice(1).map((edge, i) => edge - data.histogram.edges[i]),
+              type: 'bar',
+              marker: { color: '#2ecc71' },
+            }], {
+              title: 'Verteilung der Änderung',
+              xaxis: { title: 'Änderung in %' },
+              yaxis: { title: 'Anzahl' },
+              bargap: 0,
+            });
+
+            const select = document.getElementById('breakdown');
+            Object.keys(data.breakdowns).forEach(name => select.add(new Option(name, name)));
+            if (selected && data.breakdowns[selected]) {
+              select.value = selected;
+            }
+            if (select.options.length > 0) {
+              renderBreakdown();
+            }
+          }
+
+          load();
+        </script>
+      </body>
+    </html>
+    "#)
+}
+
+/// Korrelationen der numerischen Spalten und Zusammenhangsmaße der kategorialen Spalten
+pub async fn get_correlations(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<CorrelationParams>,
+) -> AppResult<Json<serde_json::Value>> {
+    fn list(spec: Option<&str>, default: &[&str]) -> Vec<String> {
+        match spec {
+            Some(spec) => spec.split(',').map(str::trim).filter(|c| !c.is_empty()).map(str::to_string).collect(),
+            None => default.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+    let numeric = list(params.columns.as_deref(), &DEFAULT_NUMERIC_COLUMNS);
+    let categorical = list(params.categorical.as_deref(), &DEFAULT_CATEGORICAL_COLUMNS);
+
+    let guard = lock_state(&state);
+    let df = prepare(&guard.datasets.get(params.dataset.as_deref())?.df, params.year, &params.outliers)?;
+    Ok(Json(calculate_correlations(
+        &df,
+        &numeric,
+        &categorical,
+        params.min_count.unwrap_or(DEFAULT_MIN_GROUP_SIZE),
+    )?))
+}
+
+/// Zeilen, die in einer Spalte als Ausreißer gelten, zur Durchsicht
+pub async fn get_outliers(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<OutlierListParams>,
+) -> AppResult<Json<serde_json::Value>> {
+    let guard = lock_state(&state);
+    let column = params.column.unwrap_or_else(|| Col::Salary.key().to_string());
+    // Ohne `method` gilt das Verfahren der Startkonfiguration für diese Spalte
+    let method = match &params.method {
+        Some(spec) => {
+            OutlierRule::parse(&column, spec)
+                .map_err(AppError::InvalidParameter)?
+                .method
+        }
+        None => guard
+            .outlier_rules
+            .iter()
+            .find(|rule| rule.column == column)
+            .map(|rule| rule.method)
+            .unwrap_or(OutlierMethod::Iqr { k: DEFAULT_IQR_K }),
+    };
+
+    let df = filter_year(&guard.datasets.get(params.dataset.as_deref())?.df, params.year)?;
+    Ok(Json(list_outliers(&df, &column, &method)?))
 }
 
 pub struct AppState {
-    pub df: DataFrame,
+    /// Geladene Datensätze (`prepared`, `unprepared-<jahr>`, `uploaded-<id>`)
+    pub datasets: DatasetRegistry,
+    /// Imputationsregeln aus der Startkonfiguration, gelten auch für Uploads
+    pub imputation_policies: Vec<ImputationPolicy>,
+    /// Ausreißer-Regeln aus der Startkonfiguration, gelten auch für Uploads
+    pub outlier_rules: Vec<OutlierRule>,
+    /// Stand des automatischen Neuladens der Quelldateien
+    pub reload: ReloadStatus,
+    /// Quelldateien; neue Antworten werden an die des aktuellen Jahrgangs angehängt
+    pub sources: Arc<SurveySources>,
 }
 
 #[derive(Deserialize)]
 struct PredictParams {
     experience: f64,
+    dataset: Option<String>,
+    year: Option<i32>,
+    metric: Option<String>,
+    #[serde(flatten)]
+    outliers: OutlierParams,
+}
+
+/// Auswahl des Datensatzes; ohne Angabe gilt `prepared`
+#[derive(Deserialize)]
+struct DatasetParams {
+    dataset: Option<String>,
+}
+
+/// Ausreißer-Behandlung für eine Anfrage, z. B. `?outliers=iqr:1.5:drop`
+/// oder `?outliers=percentile:1-99&outlier_column=experience`
+#[derive(Deserialize)]
+pub struct OutlierParams {
+    outliers: Option<String>,
+    outlier_column: Option<String>,
+}
+
+/// Optionaler Filter auf ein Erhebungsjahr und Kennzahl (`metric=base|bonus|total`)
+#[derive(Deserialize)]
+pub struct YearParams {
+    dataset: Option<String>,
+    year: Option<i32>,
+    metric: Option<String>,
+    #[serde(flatten)]
+    outliers: OutlierParams,
+}
+
+/// Jahr, Kennzahl und Mindestgröße für gruppierte Kennzahlen
+#[derive(Deserialize)]
+pub struct GroupSummaryParams {
+    dataset: Option<String>,
+    year: Option<i32>,
+    metric: Option<String>,
+    min_count: Option<usize>,
+    #[serde(flatten)]
+    outliers: OutlierParams,
+    #[serde(flatten)]
+    bootstrap: BootstrapParams,
+}
+
+/// Jahr, Kennzahl (ohne Angabe alle drei) und Bootstrap-Optionen für `/eda-summary`
+#[derive(Deserialize)]
+pub struct SummaryParams {
+    dataset: Option<String>,
+    year: Option<i32>,
+    metric: Option<String>,
+    #[serde(flatten)]
+    outliers: OutlierParams,
+    #[serde(flatten)]
+    bootstrap: BootstrapParams,
+}
+
+/// Konfidenzintervalle auf Anfrage, z. B. `?ci=bca&resamples=2000&seed=7&confidence=0.9`; ohne `ci` (oder mit `ci=none`) keine.
+/// Die Zahlen kommen als Text, da serde in eingebetteten (`flatten`) Structs keine Zahlen aus der Query liest.
+#[derive(Deserialize)]
+pub struct BootstrapParams {
+    ci: Option<String>,
+    resamples: Option<String>,
+    seed: Option<String>,
+    confidence: Option<String>,
+}
+
+impl BootstrapParams {
+    fn config(&self) -> AppResult<Option<BootstrapConfig>> {
+        fn number<T: std::str::FromStr>(name: &str, value: &Option<String>) -> AppResult<Option<T>> {
+            value
+                .as_deref()
+                .map(|v| v.trim().parse::<T>())
+                .transpose()
+                .map_err(|_| AppError::InvalidParameter(format!("{name} ist keine gültige Zahl")))
+        }
+        BootstrapConfig::from_params(
+            self.ci.as_deref(),
+            number("resamples", &self.resamples)?,
+            number("seed", &self.seed)?,
+            number("confidence", &self.confidence)?,
+        )
+            .map_err(AppError::InvalidParameter)
+    }
+}
+
+#[derive(Deserialize)]
+struct HistogramParams {
+    dataset: Option<String>,
+    year: Option<i32>,
+    metric: Option<String>,
+    /// Ältere Form von `strategy=width&bins=<Breite>`
+    bin_size: Option<String>,
+    #[serde(flatten)]
+    outliers: OutlierParams,
+    #[serde(flatten)]
+    binning: BinParams,
+}
+
+/// Jahr, Kennzahl und Klassen für `/distribution-data`, z. B. `?metric=total&strategy=quantile&bins=10`
+#[derive(Deserialize)]
+pub struct DistributionParams {
+    dataset: Option<String>,
+    year: Option<i32>,
+    metric: Option<String>,
+    #[serde(flatten)]
+    outliers: OutlierParams,
+    #[serde(flatten)]
+    binning: BinParams,
+}
+
+/// Klasseneinteilung: `strategy` (width, count, sturges, scott, fd, quantile, edges) und `bins`
+/// (Anzahl, Breite oder Grenzen wie `0,40000,60000,100000`)
+#[derive(Deserialize)]
+pub struct BinParams {
+    bins: Option<String>,
+    strategy: Option<String>,
+}
+
+impl BinParams {
+    /// Verfahren aus der Anfrage oder `default`, wenn keiner der Parameter gesetzt ist
+    fn strategy(&self, default: BinStrategy) -> AppResult<BinStrategy> {
+        Ok(BinStrategy::parse(self.strategy.as_deref(), self.bins.as_deref())
+            .map_err(AppError::InvalidParameter)?
+            .unwrap_or(default))
+    }
+}
+
+/// Spalte(n) und Optionen für `/stats`, z. B. `?column=bonus&quantiles=p10,p90`,
+/// `?columns=salary,age,gender&top=5` oder `?metric=total`
+#[derive(Deserialize)]
+pub struct StatsParams {
+    dataset: Option<String>,
+    year: Option<i32>,
+    column: Option<String>,
+    columns: Option<String>,
+    metric: Option<String>,
+    quantiles: Option<String>,
+    top: Option<usize>,
+    #[serde(flatten)]
+    outliers: OutlierParams,
+}
+
+/// Gruppierung für `/group-stats`, z. B.
+/// `?by=seniority,company_size&metric=salary&agg=median,p25,p75&sort=median&order=desc&min_size=10&top=20`
+#[derive(Deserialize)]
+pub struct GroupStatsParams {
+    dataset: Option<String>,
+    year: Option<i32>,
+    by: String,
+    metric: Option<String>,
+    agg: Option<String>,
+    sort: Option<String>,
+    order: Option<String>,
+    min_size: Option<usize>,
+    top: Option<usize>,
+    #[serde(flatten)]
+    outliers: OutlierParams,
+    #[serde(flatten)]
+    bootstrap: BootstrapParams,
+}
+
+/// Kennzahl und Gruppierung für `/compare`, z. B. `?column=salary&group=Gender&groups=Male,Female`
+/// oder `?metric=total&group=Gender`
+#[derive(Deserialize)]
+pub struct CompareParams {
+    dataset: Option<String>,
+    year: Option<i32>,
+    #[serde(alias = "metric")]
+    column: Option<String>,
+    group: String,
+    groups: Option<String>,
+    min_size: Option<usize>,
+    #[serde(flatten)]
+    outliers: OutlierParams,
+}
+
+/// Spalte, Gruppierung und Schätzer für `/density`, z. B.
+/// `?column=salary&group=gender&kernel=epanechnikov&bandwidth=scott&points=300` (`metric` wie `column`)
+#[derive(Deserialize)]
+pub struct DensityParams {
+    dataset: Option<String>,
+    year: Option<i32>,
+    #[serde(alias = "metric")]
+    column: Option<String>,
+    group: Option<String>,
+    kernel: Option<String>,
+    bandwidth: Option<String>,
+    points: Option<usize>,
+    min_count: Option<usize>,
+    #[serde(flatten)]
+    outliers: OutlierParams,
+}
+
+/// Aufschlüsselungen, Grenzen und Klassen für `/raises/data`, z. B.
+/// `?by=seniority,lost_job_covid&max_increase=50&include_flagged=true&strategy=width&bins=2.5`
+#[derive(Deserialize)]
+pub struct RaiseParams {
+    dataset: Option<String>,
+    year: Option<i32>,
+    by: Option<String>,
+    min_size: Option<usize>,
+    max_increase: Option<f64>,
+    max_decrease: Option<f64>,
+    include_flagged: Option<bool>,
+    #[serde(flatten)]
+    outliers: OutlierParams,
+    #[serde(flatten)]
+    binning: BinParams,
+}
+
+/// Spalten für `/correlations`, z. B. `?columns=salary,bonus,age&categorical=gender,seniority&min_count=20`;
+/// eine leere Liste (`categorical=`) lässt den Teil weg
+#[derive(Deserialize)]
+pub struct CorrelationParams {
+    dataset: Option<String>,
+    year: Option<i32>,
+    columns: Option<String>,
+    categorical: Option<String>,
+    min_count: Option<usize>,
+    #[serde(flatten)]
+    outliers: OutlierParams,
+}
+
+/// Spalte, Verfahren (Kurzform wie bei `outliers`) und Jahr für `/outliers`
+#[derive(Deserialize)]
+pub struct OutlierListParams {
+    dataset: Option<String>,
+    year: Option<i32>,
+    column: Option<String>,
+    method: Option<String>,
+}
+
+/// State sperren. Ein vergifteter Mutex wird weiterverwendet, da der State
+/// nur als Ganzes ersetzt wird und daher nie halb geändert sein kann.
+pub fn lock_state(state: &Mutex<AppState>) -> MutexGuard<'_, AppState> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Nach Jahr filtern und die angefragte Ausreißer-Regel anwenden (Standardspalte: Gehalt)
+fn prepare<'a>(
+    df: &'a DataFrame,
+    year: Option<i32>,
+    params: &OutlierParams,
+) -> AppResult<Cow<'a, DataFrame>> {
+    let df = filter_year(df, year)?;
+
+    let spec = match params.outliers.as_deref() {
+        None | Some("none") => return Ok(df),
+        Some(spec) => spec,
+    };
+    let column = params.outlier_column.as_deref().unwrap_or(Col::Salary.key());
+    let rule = OutlierRule::parse(column, spec).map_err(AppError::InvalidParameter)?;
+
+    let mut df = df.into_owned();
+    apply_rule(&mut df, &rule)?;
+    Ok(Cow::Owned(df))
+}
+
+/// Wie `prepare`, aber als eigenes DataFrame, damit die Auswertung ohne gesperrten State laufen kann
+fn prepared(
+    state: &Mutex<AppState>,
+    dataset: Option<&str>,
+    year: Option<i32>,
+    params: &OutlierParams,
+) -> AppResult<DataFrame> {
+    let guard = lock_state(state);
+    Ok(prepare(&guard.datasets.get(dataset)?.df, year, params)?.into_owned())
+}
+
+/// Rechenintensive Auswertung (z. B. mit Bootstrap) im Blocking-Pool statt im async-Worker ausführen
+async fn run_blocking<T: Send + 'static>(task: impl FnOnce() -> AppResult<T> + Send + 'static) -> AppResult<T> {
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(|e| AppError::Data(polars_err!(ComputeError: "Auswertung abgebrochen: {}", e)))?
+}
+
+/// Upload-Seite (static/index.html)
+async fn show_upload_page() -> Html<&'static str> {
+    Html(include_str!("../static/index.html"))
+}
+
+/// Nimmt eine CSV-Datei als Multipart-Upload an und legt sie als neuen Datensatz `uploaded-<id>` ab
+/// (höchstens `MAX_USER_DATASETS` Uploads und Varianten, siehe `DatasetRegistry::insert_user`)
+async fn upload_csv(
+    State(state): State<Arc<Mutex<AppState>>>,
+    mut multipart: Multipart,
+) -> AppResult<Json<serde_json::Value>> {
+    let mut upload = None;
+    while let Some(field) = multipart.next_field().await? {
+        if field.name() == Some("file") {
+            let file_name = field.file_name().unwrap_or("upload.csv").to_string();
+            let data = field.bytes().await?;
+            upload = Some((file_name, data));
+            break;
+        }
+    }
+
+    let (file_name, bytes) = upload
+        .ok_or_else(|| AppError::InvalidParameter("Feld 'file' fehlt im Upload".to_string()))?;
+    if bytes.is_empty() {
+        return Err(AppError::InvalidParameter("Die hochgeladene Datei ist leer".to_string()));
+    }
+
+    // Fehler in den hochgeladenen Daten sind Fehler der Eingabe, nicht des Servers
+    let invalid = |context: &str| {
+        let context = context.to_string();
+        move |e: PolarsError| AppError::InvalidInput(format!("{context}: {e}"))
+    };
+
+    let (mut df, report) = load_data_from_reader(Cursor::new(bytes), &file_name)
+        .map_err(invalid("CSV konnte nicht gelesen werden"))?;
+    let (imputation_policies, outlier_rules) = {
+        let guard = lock_state(&state);
+        (guard.imputation_policies.clone(), guard.outlier_rules.clone())
+    };
+    let cleaning_steps = prepare_survey(&mut df, &imputation_policies, &outlier_rules)
+        .map_err(invalid("Aufbereitung fehlgeschlagen"))?;
+    if df.height() == 0 {
+        return Err(AppError::EmptyDataset("Nach der Bereinigung sind keine Zeilen übrig".to_string()));
+    }
+
+    let (rows, columns) = df.shape();
+    let dropped_rows = report.dropped_rows;
+    let mut guard = lock_state(&state);
+    let name = guard.datasets.next_upload_name();
+    let removed = guard
+        .datasets
+        .insert_user(Dataset::new(&name, DatasetKind::Upload, df, vec![file_name], cleaning_steps, vec![report]));
+    Ok(Json(json!({
+        "dataset": name,
+        "rows": rows,
+        "columns": columns,
+        "dropped_rows": dropped_rows,
+        "removed_datasets": removed,
+    })))
+}
+
+/// Aufbereitungsvariante für `POST /datasets/variants`
+#[derive(Deserialize)]
+struct VariantRequest {
+    /// Kurzname, der Datensatz heißt dann `variant-<name>`
+    name: String,
+    /// Ohne Angabe die Regeln aus der Startkonfiguration
+    imputation: Option<Vec<ImputationPolicy>>,
+    outliers: Option<Vec<OutlierRule>>,
+}
+
+/// Die geladenen Jahrgänge mit eigenen Imputations- und Ausreißer-Regeln aufbereiten und als
+/// `variant-<name>` ablegen, um Auswertungen mit `dataset=` gegen `prepared` zu vergleichen
+async fn create_variant(
+    State(state): State<Arc<Mutex<AppState>>>,
+    payload: Result<Json<VariantRequest>, JsonRejection>,
+) -> AppResult<(StatusCode, Json<serde_json::Value>)> {
+    let Json(request) = payload.map_err(|e| AppError::InvalidInput(e.body_text()))?;
+    let valid_name = !request.name.is_empty()
+        && request.name.len() <= 40
+        && request.name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !valid_name {
+        return Err(AppError::InvalidParameter(format!(
+            "'{}' ist kein gültiger Name (1 bis 40 Zeichen aus a-z, 0-9, '-' und '_')",
+            request.name
+        )));
+    }
+    for rule in request.outliers.iter().flatten() {
+        rule.method.validate().map_err(AppError::InvalidParameter)?;
+    }
+
+    let (years, imputation_policies, outlier_rules) = {
+        let guard = lock_state(&state);
+        let years = guard
+            .datasets
+            .of_kind(DatasetKind::Unprepared)
+            .map(|d| (d.df.clone(), d.info.source.clone(), d.reports.clone()))
+            .collect::<Vec<_>>();
+        (
+            years,
+            request.imputation.unwrap_or_else(|| guard.imputation_policies.clone()),
+            request.outliers.unwrap_or_else(|| guard.outlier_rules.clone()),
+        )
+    };
+    if years.is_empty() {
+        return Err(AppError::EmptyDataset(
+            "Keine Jahrgänge vor der Aufbereitung geladen (nur Cache-Dateien vorhanden)".to_string(),
+        ));
+    }
+
+    let (frames, sources, steps, reports) = run_blocking(move || {
+        let (mut frames, mut sources, mut steps, mut reports) = (Vec::new(), Vec::new(), Vec::<String>::new(), Vec::new());
+        for (mut df, source, report) in years {
+            let year_steps = prepare_survey(&mut df, &imputation_policies, &outlier_rules)
+                .map_err(|e| AppError::InvalidInput(format!("Aufbereitung fehlgeschlagen: {e}")))?;
+            for step in year_steps {
+                if !steps.contains(&step) {
+                    steps.push(step);
+                }
+            }
+            frames.push(df);
+            sources.extend(source);
+            reports.extend(report);
+        }
+        Ok((frames, sources, steps, reports))
+    })
+    .await?;
+    let df = merge_years(frames)?;
+    if df.height() == 0 {
+        return Err(AppError::EmptyDataset("Nach der Bereinigung sind keine Zeilen übrig".to_string()));
+    }
+
+    let name = variant_dataset_name(&request.name);
+    let (rows, columns) = df.shape();
+    let mut guard = lock_state(&state);
+    let removed = guard
+        .datasets
+        .insert_user(Dataset::new(&name, DatasetKind::Variant, df, sources, steps.clone(), reports));
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "dataset": name,
+            "rows": rows,
+            "columns": columns,
+            "cleaning_steps": steps,
+            "removed_datasets": removed,
+        })),
+    ))
+}
+
+/// Neue Antwort prüfen, in der Datenbank speichern und neu laden,
+/// damit Kennzahlen und Cache die Antwort sofort enthalten
+async fn submit_response(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<DatasetParams>,
+    payload: Result<Json<SurveyRecord>, JsonRejection>,
+) -> AppResult<(StatusCode, Json<serde_json::Value>)> {
+    let Json(mut record) = payload.map_err(|e| AppError::InvalidInput(e.body_text()))?;
+    validate_submission(&mut record).map_err(AppError::InvalidRecord)?;
+    if record.timestamp.as_deref().is_none_or(|t| t.trim().is_empty()) {
+        record.timestamp = Some(submission_timestamp());
+    }
+
+    // Nur die aus den Quelldateien geladenen Datensätze haben einen Speicher
+    let name = params.dataset.unwrap_or_else(|| DEFAULT_DATASET.to_string());
+    let (sources, path) = {
+        let guard = lock_state(&state);
+        guard.datasets.get(Some(&name))?;
+        if name != DEFAULT_DATASET && name != unprepared_dataset_name(CURRENT_YEAR) {
+            return Err(AppError::InvalidParameter(format!(
+                "Datensatz '{name}' hat keine Quelle, an die Antworten angehängt werden können"
+            )));
+        }
+        let path = guard
+            .sources
+            .files
+            .iter()
+            .find(|(year, _)| *year == CURRENT_YEAR)
+            .map(|(_, path)| path.clone())
+            .ok_or_else(|| AppError::InvalidInput(format!("Keine Quelle für {CURRENT_YEAR} konfiguriert")))?;
+        // cleaned_data.csv wird von `rust_web_app clean` neu erzeugt und darf keine Antworten aufnehmen
+        if !is_database_file(&path) {
+            return Err(AppError::InvalidInput(
+                "Antworten werden nur in der Datenbank gespeichert; zuerst `rust_web_app import-db` ausführen"
+                    .to_string(),
+            ));
+        }
+        (guard.sources.clone(), path)
+    };
+
+    let stored_path = path.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        append_response(&path, CURRENT_YEAR, &record)?;
+        Ok::<_, PolarsError>(sources.load().map_err(|e| e.to_string()))
+    })
+    .await
+    .map_err(|e| AppError::Data(polars_err!(ComputeError: "Speichern abgebrochen: {}", e)))??;
+
+    let mut guard = lock_state(&state);
+    apply_reload(&mut guard, result);
+    let rows = guard.datasets.get(Some(&name))?.info.rows;
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "dataset": name,
+            "stored_in": stored_path,
+            "rows": rows,
+            "reload": guard.reload.state,
+            "reload_error": guard.reload.last_error,
+        })),
+    ))
+}
+
+/// Alle geladenen Datensätze mit Metadaten
+async fn list_datasets(State(state): State<Arc<Mutex<AppState>>>) -> Json<Vec<DatasetInfo>> {
+    let guard = lock_state(&state);
+    Json(guard.datasets.list())
+}
+
+/// Metadaten und Schema eines Datensatzes
+async fn get_dataset(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Path(name): Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    let guard = lock_state(&state);
+    Ok(Json(guard.datasets.get(Some(&name))?.describe()))
+}
+
+/// Datensatz entfernen (nicht den Standarddatensatz)
+async fn delete_dataset(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Path(name): Path<String>,
+) -> AppResult<Json<DatasetInfo>> {
+    let mut guard = lock_state(&state);
+    Ok(Json(guard.datasets.remove(&name)?.info))
+}
+
+/// Stand des automatischen Neuladens (Zeitpunkt, Schlüssel, letzter Fehler)
+async fn get_reload_status(State(state): State<Arc<Mutex<AppState>>>) -> Json<ReloadStatus> {
+    let guard = lock_state(&state);
+    Json(guard.reload.clone())
+}
+
+/// Validierungsberichte eines Datensatzes
+async fn get_validation_report(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<DatasetParams>,
+) -> AppResult<Json<Vec<ValidationReport>>> {
+    let guard = lock_state(&state);
+    Ok(Json(guard.datasets.get(params.dataset.as_deref())?.reports.clone()))
+}
+
+/// Histogrammdaten für die Upload-Seite, optional für ein Erhebungsjahr
+async fn get_histogram(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<HistogramParams>,
+) -> AppResult<Json<HistogramData>> {
+    let strategy = match (&params.bin_size, &params.binning.strategy, &params.binning.bins) {
+        (Some(bin_size), None, None) => BinStrategy::parse(Some("width"), Some(bin_size))
+            .map_err(AppError::InvalidParameter)?
+            .unwrap_or(BinStrategy::FixedWidth(DEFAULT_HISTOGRAM_BIN_SIZE)),
+        _ => params.binning.strategy(BinStrategy::FixedWidth(DEFAULT_HISTOGRAM_BIN_SIZE))?,
+    };
+
+    let metric = parse_metric(params.metric.as_deref())?;
+    let guard = lock_state(&state);
+    let df = prepare(&guard.datasets.get(params.dataset.as_deref())?.df, params.year, &params.outliers)?;
+    ensure_rows(&df)?;
+    Ok(Json(calculate_histogram(&df, &metric.column(&df)?, &strategy)?))
 }
 
 pub fn create_router(state: Arc<Mutex<AppState>>) -> Router {
@@ -204,7 +1129,33 @@ pub fn create_router(state: Arc<Mutex<AppState>>) -> Router {
         .route("/predict", get(show_predict)) // Seite für Gehaltsvorhersage
         .route("/predict-salary", get(predict_salary))
         .route("/eda-summary", get(eda_summary)) // Statistiken
+        .route("/eda-summary/by-city", get(eda_summary_by_city)) // Statistiken je Ort
+        .route("/eda-summary/by-state", get(eda_summary_by_state)) // Statistiken je Bundesland
+        .route("/distribution/by", get(show_group_distribution)) // Verteilung je Gruppe
+        .route("/distribution/by/data", get(get_group_distribution))
         .route("/distribution-data", get(get_distribution_data)) // Verteilung// API für Gehaltsvorhersage
+        .route(
+            "/upload",
+            get(show_upload_page)
+                .post(upload_csv)
+                .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES)),
+        ) // CSV-Upload
+        .route("/histogram", get(get_histogram)) // Histogramm für die Upload-Seite
+        .route("/validation-report", get(get_validation_report)) // Verworfene Zeilen beim Laden
+        .route("/tech-summary", get(tech_summary)) // Gehalt je Technologie
+        .route("/stats", get(get_column_stats)) // Kennzahlen beliebiger Spalten
+        .route("/group-stats", get(get_group_stats)) // Kennzahlen je Gruppe
+        .route("/density", get(get_density)) // Kerndichteschätzung
+        .route("/compare", get(compare)) // Signifikanztests zwischen Gruppen
+        .route("/correlations", get(get_correlations)) // Korrelationen und Zusammenhangsmaße
+        .route("/raises", get(show_raises)) // Gehaltsänderung zum Vorjahr
+        .route("/raises/data", get(get_raises))
+        .route("/outliers", get(get_outliers)) // Ausreißer zur Durchsicht
+        .route("/reload-status", get(get_reload_status)) // Stand des automatischen Neuladens
+        .route("/responses", get(show_response_form).post(submit_response)) // Neue Antworten erfassen
+        .route("/datasets", get(list_datasets)) // Geladene Datensätze
+        .route("/datasets/variants", post(create_variant)) // Aufbereitung mit eigenen Regeln
+        .route("/datasets/:name", get(get_dataset).delete(delete_dataset)) // Datensatz ansehen/entfernen
         .with_state(state)
 }
 
@@ -238,7 +1189,7 @@ async fn show_index() -> impl IntoResponse {
             main {
                 padding: 2rem;
             }
-            #chart, #distribution-chart {
+            #chart, #distribution-chart, #correlation-chart {
                 width: 90%;
                 max-width: 800px;
                 height: 600px;
@@ -283,12 +1234,32 @@ async fn show_index() -> impl IntoResponse {
             <p>Scatterplot und Gehaltsverteilung:</p>
             <div id="chart"></div>
             <h2>Gehaltsverteilung</h2>
+            <label>
+                <input type="checkbox" id="show-density" checked onchange="fetchDistributionData()">
+                Dichtekurve überlagern
+            </label>
+            <select id="density-kernel" onchange="fetchDistributionData()">
+                <option value="gaussian">Gauß</option>
+                <option value="epanechnikov">Epanechnikov</option>
+            </select>
             <div id="distribution-chart"></div>
+            <h2>Zusammenhänge</h2>
+            <select id="correlation-measure" onchange="renderCorrelations()">
+                <option value="pearson">Pearson</option>
+                <option value="spearman">Spearman</option>
+                <option value="cramers_v">Cramérs V (kategorial)</option>
+                <option value="eta">η (kategorial × numerisch)</option>
+            </select>
+            <div id="correlation-chart"></div>
             <h2>EDA Ergebnisse</h2>
             <div id="eda-summary">
                 <!-- EDA-Zusammenfassung wird hier eingefügt -->
             </div>
             <button onclick="location.href='/predict'">Predict Now</button>
+            <button onclick="location.href='/upload'">CSV hochladen</button>
+            <button onclick="location.href='/distribution/by?group=gender'">Verteilung nach Geschlecht</button>
+            <button onclick="location.href='/raises'">Gehaltsänderung</button>
+            <button onclick="location.href='/responses'">Antwort erfassen</button>
         </main>
         <footer>
             &copy; 2025 Gehaltsanalyse. Alle Rechte vorbehalten.
@@ -316,25 +1287,88 @@ async fn show_index() -> impl IntoResponse {
                 Plotly.newPlot('chart', [trace], layout);
             }
 
-            // Funktion, um die Verteilungsdaten zu laden
+            // Funktion, um die Verteilungsdaten zu laden, optional mit Dichtekurve (KDE)
             async function fetchDistributionData() {
                 const response = await fetch('/distribution-data');
                 const data = await response.json();
 
-                const trace = {
-                    x: data.bins,
-                    y: data.frequencies,
+                const traces = [{
+                    x: data.centers,
+                    y: data.counts,
+                    width: data.edges.slice(1).map((edge, i) => edge - data.edges[i]),
                     type: 'bar',
+                    name: 'Histogramm',
                     marker: { color: 'blue' },
-                };
+                    opacity: 0.6,
+                }];
 
                 const layout = {
                     title: 'Salary Distribution',
                     xaxis: { title: 'Salary (EUR)' },
                     yaxis: { title: 'Frequency' },
+                    yaxis2: { title: 'Dichte', overlaying: 'y', side: 'right', showgrid: false },
+                    bargap: 0,
                 };
 
-                Plotly.newPlot('distribution-chart', [trace], layout);
+                if (document.getElementById('show-density').checked) {
+                    const kernel = document.getElementById('density-kernel').value;
+                    const densityResponse = await fetch('/density?kernel=' + kernel);
+                    const density = await densityResponse.json();
+                    if (densityResponse.ok) {
+                        traces.push({
+                            x: density.grid,
+                            y: density.groups[0].density,
+                            type: 'scatter',
+                            mode: 'lines',
+                            name: `KDE (${density.kernel}, h = ${density.groups[0].bandwidth.toFixed(0)})`,
+                            line: { color: 'red', width: 2 },
+                            yaxis: 'y2',
+                        });
+                    }
+                }
+
+                Plotly.newPlot('distribution-chart', traces, layout);
+            }
+
+            // Korrelationen einmal laden, die Auswahl wechselt nur die Heatmap
+            let correlations = null;
+
+            async function fetchCorrelations() {
+                const response = await fetch('/correlations');
+                if (!response.ok) {
+                    return;
+                }
+                correlations = await response.json();
+                renderCorrelations();
+            }
+
+            function renderCorrelations() {
+                const measure = document.getElementById('correlation-measure').value;
+                const rows = measure === 'pearson' || measure === 'spearman'
+                    ? correlations.numeric
+                    : correlations.categorical;
+                const columns = measure === 'cramers_v' ? correlations.categorical : correlations.numeric;
+                const matrix = correlations[measure];
+                const signed = measure === 'pearson' || measure === 'spearman';
+
+                Plotly.newPlot('correlation-chart', [{
+                    z: matrix.map(row => row.map(cell => cell.value)),
+                    x: columns,
+                    y: rows,
+                    text: matrix.map(row => row.map(cell =>
+                        cell.value === null ? `n = ${cell.n}` : `${cell.value.toFixed(2)} (n = ${cell.n})`)),
+                    hoverinfo: 'x+y+text',
+                    texttemplate: '%{z:.2f}',
+                    type: 'heatmap',
+                    colorscale: signed ? 'RdBu' : 'Blues',
+                    reversescale: !signed,
+                    zmin: signed ? -1 : 0,
+                    zmax: 1,
+                }], {
+                    title: document.getElementById('correlation-measure').selectedOptions[0].text,
+                    margin: { l: 200, b: 150 },
+                    yaxis: { autorange: 'reversed' },
+                });
             }
 
             // Funktion, um die EDA-Zusammenfassung zu laden und anzuzeigen
@@ -342,15 +1376,19 @@ async fn show_index() -> impl IntoResponse {
                 const response = await fetch('/eda-summary');
                 const data = await response.json();
 
-                // Formatierte HTML-Ausgabe der EDA-Ergebnisse
-                const salaryStats = `
-                    <h3>Gehalt</h3>
-                    <p><strong>Mittelwert:</strong> ${data.salary.mean.toFixed(2)} EUR</p>
-                    <p><strong>Median:</strong> ${data.salary.median.toFixed(2)} EUR</p>
-                    <p><strong>Standardabweichung:</strong> ${data.salary.std_dev.toFixed(2)}</p>
-                    <p><strong>Minimum:</strong> ${data.salary.min.toFixed(2)} EUR</p>
-                    <p><strong>Maximum:</strong> ${data.salary.max.toFixed(2)} EUR</p>
-                `;
+                // Formatierte HTML-Ausgabe der EDA-Ergebnisse, je Vergütungsbestandteil
+                const compensationStats = [
+                    ['salary', 'Grundgehalt'],
+                    ['bonus', 'Bonus und Aktien'],
+                    ['total_compensation', 'Gesamtvergütung'],
+                ].map(([key, title]) => `
+                    <h3>${title}</h3>
+                    <p><strong>Mittelwert:</strong> ${data[key].mean.toFixed(2)} EUR</p>
+                    <p><strong>Median:</strong> ${data[key].median.toFixed(2)} EUR</p>
+                    <p><strong>Standardabweichung:</strong> ${data[key].std_dev.toFixed(2)}</p>
+                    <p><strong>Minimum:</strong> ${data[key].min.toFixed(2)} EUR</p>
+                    <p><strong>Maximum:</strong> ${data[key].max.toFixed(2)} EUR</p>
+                `).join('');
 
                 const experienceStats = `
                     <h3>Erfahrung</h3>
@@ -364,7 +1402,7 @@ async fn show_index() -> impl IntoResponse {
                 // EDA-Ergebnisse in den entsprechenden Div einfügen
                 document.getElementById('eda-summary').innerHTML = `
                     <h2>EDA Zusammenfassung</h2>
-                    ${salaryStats}
+                    ${compensationStats}
                     ${experienceStats}
                 `;
             }
@@ -372,6 +1410,7 @@ async fn show_index() -> impl IntoResponse {
             // Lade die Daten beim Start
             fetchScatterData();
             fetchDistributionData();
+            fetchCorrelations();
             fetchEDASummary();
         </script>
     </body>
@@ -383,30 +1422,31 @@ async fn show_index() -> impl IntoResponse {
 
 
 /// API, um Scatterplot-Daten als JSON bereitzustellen
-async fn get_scatter_data(State(state): State<Arc<Mutex<AppState>>>) -> Json<serde_json::Value> {
-    let guard = state.lock().unwrap();
-    let df = &guard.df;
-
-    let experience = df
-        .column("Total years of experience")
-        .expect("Spalte nicht gefunden")
-        .f64()
-        .expect("Spalte ist nicht vom Typ f64")
-        .into_no_null_iter()
-        .collect::<Vec<f64>>();
-
-    let salary = df
-        .column("Yearly brutto salary (without bonus and stocks) in EUR")
-        .expect("Spalte nicht gefunden")
-        .f64()
-        .expect("Spalte ist nicht vom Typ f64")
-        .into_no_null_iter()
-        .collect::<Vec<f64>>();
-
-    Json(json!({
+async fn get_scatter_data(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(params): Query<YearParams>,
+) -> AppResult<Json<serde_json::Value>> {
+    let guard = lock_state(&state);
+    let df = prepare(&guard.datasets.get(params.dataset.as_deref())?.df, params.year, &params.outliers)?;
+    let metric = parse_metric(params.metric.as_deref())?;
+    let (experience, salary) = experience_and_metric(&df, metric)?;
+
+    Ok(Json(json!({
+        "metric": metric.label(),
         "experience": experience,
         "salary": salary,
-    }))
+    })))
+}
+
+/// Erfahrung und Kennzahl der Befragten, bei denen beide Werte vorhanden sind
+fn experience_and_metric(df: &DataFrame, metric: Metric) -> AppResult<(Vec<f64>, Vec<f64>)> {
+    let experience = numeric_column(df, Col::Experience)?;
+    let values = numeric_series(df, &metric.column(df)?)?;
+    Ok(experience
+        .into_iter()
+        .zip(&values)
+        .filter_map(|(x, y)| Some((x?, y?)))
+        .unzip())
 }
 
 /// Seite für Gehaltsvorhersage
@@ -500,29 +1540,204 @@ async fn show_predict() -> impl IntoResponse {
     Html(html.to_string())
 }
 
-/// API, um Gehaltsvorhersage basierend auf Erfahrung zu berechnen
+/// Formular für neue Antworten (sendet an `POST /responses`)
+async fn show_response_form() -> impl IntoResponse {
+    let html = r#"
+    <!DOCTYPE html>
+    <html lang="de">
+    <head>
+        <meta charset="UTF-8">
+        <meta name="viewport" content="width=device-width, initial-scale=1.0">
+        <title>Survey Response</title>
+        <style>
+            body {
+                font-family: Arial, sans-serif;
+                margin: 0;
+                padding: 0;
+                background-color: #f4f4f9;
+                color: #333;
+                text-align: center;
+            }
+            header {
+                background-color: #4CAF50;
+                color: white;
+                padding: 1rem;
+                font-size: 1.5rem;
+            }
+            main {
+                padding: 2rem;
+            }
+            form {
+                display: grid;
+                grid-template-columns: 1fr 1fr;
+                gap: 10px 20px;
+                max-width: 700px;
+                margin: 0 auto;
+                text-align: left;
+            }
+            label {
+                align-self: center;
+            }
+            input, select {
+                padding: 10px;
+                font-size: 16px;
+                border: 1px solid #ccc;
+                border-radius: 5px;
+            }
+            button {
+                margin-top: 10px;
+                padding: 10px 20px;
+                font-size: 16px;
+                background-color: #4CAF50;
+                color: white;
+                border: none;
+                border-radius: 5px;
+                cursor: pointer;
+            }
+            button:hover {
+                background-color: #45a049;
+            }
+            #result {
+                margin-top: 20px;
+                font-size: 18px;
+                font-weight: bold;
+            }
+            #errors {
+                color: #c0392b;
+                max-width: 700px;
+                margin: 0 auto;
+                text-align: left;
+            }
+            footer {
+                margin-top: 2rem;
+                padding: 1rem;
+                background-color: #4CAF50;
+                color: white;
+                font-size: 0.9rem;
+            }
+        </style>
+    </head>
+    <body>
+        <header>
+            Salary Survey
+        </header>
+        <main>
+            <h1>Neue Antwort erfassen</h1>
+            <p>Pflichtangaben: Erfahrung, Level und Gehalt.</p>
+            <form id="response-form" onsubmit="submitResponse(event)">
+                <label for="age">Alter</label>
+                <input type="number" id="age" data-type="number" min="14" max="100" />
+                <label for="gender">Geschlecht</label>
+                <select id="gender">{{gender}}</select>
+                <label for="city">Ort</label>
+                <input type="text" id="city" />
+                <label for="position">Position</label>
+                <input type="text" id="position" />
+                <label for="experience">Jahre an Erfahrung *</label>
+                <input type="number" id="experience" data-type="number" min="0" max="50" step="0.5" required />
+                <label for="experience_germany">davon in Deutschland</label>
+                <input type="number" id="experience_germany" data-type="number" min="0" step="0.5" />
+                <label for="seniority">Level *</label>
+                <select id="seniority" required>{{seniority}}</select>
+                <label for="main_tech">Haupttechnologie</label>
+                <input type="text" id="main_tech" />
+                <label for="other_techs">Weitere Technologien</label>
+                <input type="text" id="other_techs" placeholder="z. B. Python, SQL" />
+                <label for="salary">Bruttojahresgehalt (EUR) *</label>
+                <input type="number" id="salary" data-type="number" min="0" required />
+                <label for="bonus">Bonus + Aktien (EUR)</label>
+                <input type="number" id="bonus" data-type="number" min="0" />
+                <label for="salary_last_year">Gehalt vor einem Jahr (EUR)</label>
+                <input type="number" id="salary_last_year" data-type="number" min="0" />
+                <label for="bonus_last_year">Bonus vor einem Jahr (EUR)</label>
+                <input type="number" id="bonus_last_year" data-type="number" min="0" />
+                <label for="vacation_days">Urlaubstage</label>
+                <input type="number" id="vacation_days" data-type="number" min="0" max="365" />
+                <label for="employment_status">Anstellung</label>
+                <select id="employment_status">{{employment_status}}</select>
+                <label for="contract_duration">Vertrag</label>
+                <select id="contract_duration">{{contract_duration}}</select>
+                <label for="work_language">Arbeitssprache</label>
+                <input type="text" id="work_language" />
+                <label for="company_size">Unternehmensgröße</label>
+                <select id="company_size">{{company_size}}</select>
+                <label for="company_type">Unternehmensart</label>
+                <select id="company_type">{{company_type}}</select>
+                <label for="lost_job_covid">Job wegen Corona verloren?</label>
+                <select id="lost_job_covid">{{lost_job_covid}}</select>
+                <span></span>
+                <button type="submit">Absenden</button>
+            </form>
+            <p id="result"></p>
+            <ul id="errors"></ul>
+        </main>
+        <footer>
+            &copy; 2025 Gehaltsanalyse. Alle Rechte vorbehalten.
+        </footer>
+        <script>
+            async function submitResponse(event) {
+                event.preventDefault();
+                const record = {};
+                for (const field of document.querySelectorAll('#response-form input, #response-form select')) {
+                    if (field.value === '') {
+                        continue;
+                    }
+                    record[field.id] = field.dataset.type === 'number' ? Number(field.value) : field.value;
+                }
+
+                const response = await fetch('/responses', {
+                    method: 'POST',
+                    headers: { 'Content-Type': 'application/json' },
+                    body: JSON.stringify(record),
+                });
+                const data = await response.json();
+                const errors = document.getElementById('errors');
+                errors.innerHTML = '';
+                if (response.ok) {
+                    document.getElementById('result').innerText =
+                        `Danke! Der Datensatz ${data.dataset} enthält jetzt ${data.rows} Antworten.`;
+                    document.getElementById('response-form').reset();
+                } else {
+                    document.getElementById('result').innerText = 'Antwort nicht gespeichert:';
+                    for (const error of data.errors || [{ message: data.message }]) {
+                        const item = document.createElement('li');
+                        item.innerText = error.message;
+                        errors.appendChild(item);
+                    }
+                }
+            }
+        </script>
+    </body>
+    </html>
+    "#;
+
+    // Auswahlfelder aus den erlaubten Kategorien füllen
+    let html = SUBMISSION_CATEGORIES
+        .iter()
+        .fold(html.to_string(), |html, (col, allowed)| {
+            let options = allowed
+                .iter()
+                .map(|value| format!(r#"<option value="{value}">{value}</option>"#))
+                .collect::<String>();
+            html.replace(&format!("{{{{{}}}}}", col.key()), &format!(r#"<option value=""></option>{options}"#))
+        });
+    Html(html)
+}
+
+/// API, um Gehaltsvorhersage basierend auf Erfahrung zu berechnen (optional je Jahr und ohne Ausreißer)
 async fn predict_salary(
     State(state): State<Arc<Mutex<AppState>>>,
     Query(params): Query<PredictParams>
-) -> Json<serde_json::Value> {
-    let guard = state.lock().unwrap();
-    let df = &guard.df;
-
-    let experience = df
-        .column("Total years of experience")
-        .expect("Spalte nicht gefunden")
-        .f64()
-        .expect("Spalte ist nicht vom Typ f64")
-        .into_no_null_iter()
-        .collect::<Vec<f64>>();
-
-    let salary = df
-        .column("Yearly brutto salary (without bonus and stocks) in EUR")
-        .expect("Spalte nicht gefunden")
-        .f64()
-        .expect("Spalte ist nicht vom Typ f64")
-        .into_no_null_iter()
-        .collect::<Vec<f64>>();
+) -> AppResult<Json<serde_json::Value>> {
+    if !params.experience.is_finite() {
+        return Err(AppError::InvalidParameter("experience muss eine Zahl sein".to_string()));
+    }
+    let metric = parse_metric(params.metric.as_deref())?;
+    let guard = lock_state(&state);
+    let df = prepare(&guard.datasets.get(params.dataset.as_deref())?.df, params.year, &params.outliers)?;
+    ensure_rows(&df)?;
+
+    let (experience, salary) = experience_and_metric(&df, metric)?;
 
     let n = experience.len() as f64;
     let mean_x = experience.iter().sum::<f64>() / n;
@@ -537,10 +1752,13 @@ async fn predict_salary(
         var_x += dx * dx;
     }
 
+    if var_x == 0.0 {
+        return Err(AppError::InvalidInput("Erfahrung hat keine Streuung, keine Regression möglich".to_string()));
+    }
     let slope = cov_xy / var_x;
     let intercept = mean_y - slope * mean_x;
 
     let predicted_salary = intercept + slope * params.experience;
 
-    Json(json!({ "predicted_salary": predicted_salary }))
+    Ok(Json(json!({ "metric": metric.label(), "predicted_salary": predicted_salary })))
 }